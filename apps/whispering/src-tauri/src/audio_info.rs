@@ -0,0 +1,136 @@
+use crate::transcription::ffmpeg::ffmpeg_command;
+use serde::Serialize;
+use std::path::Path;
+
+/// Basic properties of an audio file - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioInfo {
+    pub duration_secs: f64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// 0 when the format has no fixed sample size (e.g. MP3, AAC)
+    pub bits_per_sample: u16,
+    /// `wav` for WAV files, otherwise the codec name FFmpeg reports, e.g. `mp3`
+    pub format: String,
+}
+
+/// Read a WAV file's properties from its header, without reading any samples
+fn wav_info(path: &Path) -> Result<AudioInfo, String> {
+    let reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to open WAV file {:?}: {}", path, e))?;
+    let spec = reader.spec();
+    // `len` counts samples across all channels
+    let duration_secs = reader.len() as f64 / (spec.sample_rate as f64 * spec.channels as f64);
+
+    Ok(AudioInfo {
+        duration_secs,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        bits_per_sample: spec.bits_per_sample,
+        format: "wav".to_string(),
+    })
+}
+
+/// Parse `Duration: HH:MM:SS.ss` from `ffmpeg -i` output into seconds
+fn parse_duration(stderr: &str) -> Option<f64> {
+    let line = stderr.lines().find_map(|line| line.trim().strip_prefix("Duration:"))?;
+    let timestamp = line.split(',').next()?.trim();
+    let mut parts = timestamp.split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Parse the first audio stream line of `ffmpeg -i` output, e.g.
+/// `Stream #0:0: Audio: mp3, 44100 Hz, stereo, fltp, 128 kb/s`
+///
+/// Returns the codec, sample rate, channel count and bits per sample.
+fn parse_audio_stream(stderr: &str) -> Option<(String, u32, u16, u16)> {
+    let (_, stream) = stderr.lines().find_map(|line| line.split_once("Audio: "))?;
+    let mut fields = stream.split(',').map(str::trim);
+
+    // The codec may be followed by a profile or tag, e.g. `aac (LC) (mp4a / 0x...)`
+    let codec = fields.next()?.split_whitespace().next()?.to_string();
+    let sample_rate = fields.next()?.strip_suffix(" Hz")?.parse().ok()?;
+    let channels = match fields.next()? {
+        "mono" => 1,
+        "stereo" => 2,
+        layout => layout
+            .split_whitespace()
+            .next()
+            .and_then(|count| count.parse().ok())
+            // Named layouts such as `5.1(side)` are `<front>.<lfe>`
+            .or_else(|| {
+                let (front, lfe) = layout.split('(').next()?.split_once('.')?;
+                Some(front.parse::<u16>().ok()? + lfe.parse::<u16>().ok()?)
+            })
+            .unwrap_or(0),
+    };
+    let bits_per_sample = match fields.next().unwrap_or("") {
+        // Integer formats can carry the real depth, e.g. `s32 (24 bit)`
+        format if format.contains("(24 bit)") => 24,
+        format if format.starts_with("u8") => 8,
+        format if format.starts_with("s16") => 16,
+        format if format.starts_with("s32") || format.starts_with("flt") => 32,
+        format if format.starts_with("s64") || format.starts_with("dbl") => 64,
+        _ => 0,
+    };
+
+    Some((codec, sample_rate, channels, bits_per_sample))
+}
+
+/// Probe a non-WAV file with `ffmpeg -i` and parse what it prints
+fn ffmpeg_info(path: &Path) -> Result<AudioInfo, String> {
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-hide_banner").arg("-i").arg(path);
+    // With no output file ffmpeg exits with an error after printing the input info
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run FFmpeg on {:?}: {}", path, e))?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let duration_secs = parse_duration(&stderr)
+        .ok_or_else(|| format!("FFmpeg reported no duration for {:?}", path))?;
+    let (format, sample_rate, channels, bits_per_sample) = parse_audio_stream(&stderr)
+        .ok_or_else(|| format!("FFmpeg found no audio stream in {:?}", path))?;
+
+    Ok(AudioInfo {
+        duration_secs,
+        sample_rate,
+        channels,
+        bits_per_sample,
+        format,
+    })
+}
+
+/// WAV files are read with hound, anything else is probed with FFmpeg
+fn audio_info(path: &Path) -> Result<AudioInfo, String> {
+    if !path.is_file() {
+        return Err(format!("File not found: {:?}", path));
+    }
+    wav_info(path).or_else(|_| ffmpeg_info(path))
+}
+
+/// Duration of an audio file in fractional seconds
+///
+/// WAV durations come from the header alone; other formats need FFmpeg.
+#[tauri::command]
+pub async fn get_audio_duration(file_path: String) -> Result<f64, String> {
+    audio_info(Path::new(&file_path)).map(|info| info.duration_secs)
+}
+
+/// Duration, sample rate, channels, sample size and format of an audio file
+///
+/// One call for everything the UI shows about a file; read the same way as
+/// `get_audio_duration`.
+#[tauri::command]
+pub async fn get_audio_info(file_path: String) -> Result<AudioInfo, String> {
+    let info = audio_info(Path::new(&file_path))?;
+    println!(
+        "[Audio Info] {}: {} {:.2}s, {} Hz, {} ch",
+        file_path, info.format, info.duration_secs, info.sample_rate, info.channels
+    );
+    Ok(info)
+}
@@ -0,0 +1,771 @@
+use crate::audio_info::AudioInfo;
+use crate::recorder::wav_writer::{WavWriter, WavWriterConfig};
+use crate::transcription::{build_resampler, ResampleQuality};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// Length of the analysis frames used for silence detection
+pub(crate) const FRAME_MS: u32 = 10;
+
+/// Result of removing silent regions from a recording - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceRemovalResult {
+    pub original_duration_secs: f64,
+    pub trimmed_duration_secs: f64,
+    pub regions_removed: usize,
+    /// Kept regions in order, for mapping trimmed times back to the original
+    pub mapping: Vec<KeptRegion>,
+}
+
+/// A stretch of the original recording that survived silence removal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeptRegion {
+    /// Where the region starts in the original recording
+    pub original_start_ms: i64,
+    /// Where the region starts in the trimmed recording
+    pub trimmed_start_ms: i64,
+    pub duration_ms: i64,
+}
+
+/// Translate a position in a trimmed recording back to the original recording
+///
+/// Times past the end of the trimmed audio map to the end of the last kept
+/// region. With no mapping the time is returned unchanged.
+pub fn map_trimmed_time_to_original(trimmed_ms: i64, mapping: &[KeptRegion]) -> i64 {
+    let Some(region) = mapping
+        .iter()
+        .rev()
+        .find(|region| region.trimmed_start_ms <= trimmed_ms)
+        .or(mapping.first())
+    else {
+        return trimmed_ms;
+    };
+
+    let offset = (trimmed_ms - region.trimmed_start_ms).clamp(0, region.duration_ms);
+    region.original_start_ms + offset
+}
+
+/// Read a WAV file into interleaved f32 samples normalized to [-1.0, 1.0]
+pub(crate) fn read_wav_samples(path: &Path) -> Result<(hound::WavSpec, Vec<f32>), String> {
+    let mut reader = hound::WavReader::open(path)
+        .map_err(|e| format!("Failed to open WAV file {:?}: {}", path, e))?;
+    let spec = reader.spec();
+
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader
+            .samples::<f32>()
+            .collect::<std::result::Result<Vec<_>, _>>(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample - 1)) as f32;
+            reader
+                .samples::<i32>()
+                .map(|s| s.map(|sample| sample as f32 / scale))
+                .collect::<std::result::Result<Vec<_>, _>>()
+        }
+    }
+    .map_err(|e| format!("Failed to read samples from {:?}: {}", path, e))?;
+
+    Ok((spec, samples))
+}
+
+/// Write interleaved f32 samples to a new WAV file using the recorder's writer
+pub(crate) fn write_wav_samples(
+    path: &Path,
+    sample_rate: u32,
+    channels: u16,
+    samples: &[f32],
+) -> Result<(), String> {
+    let config = WavWriterConfig::default();
+    let mut writer = WavWriter::new(PathBuf::from(path), sample_rate, channels, config)
+        .map_err(|e| format!("Failed to create WAV file {:?}: {}", path, e))?;
+    writer
+        .write_samples_f32(samples)
+        .map_err(|e| format!("Failed to write samples: {}", e))?;
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV: {}", e))
+}
+
+/// Convert a mean square sample value to an RMS level in dBFS
+pub(crate) fn rms_db(mean_square: f32) -> f32 {
+    20.0 * mean_square.sqrt().max(1e-10).log10()
+}
+
+/// Classify each analysis frame as silent (true) or not, using RMS in dBFS
+fn silent_frames(samples: &[f32], channels: usize, frame_len: usize, threshold_db: f32) -> Vec<bool> {
+    samples
+        .chunks(frame_len * channels)
+        .map(|frame| {
+            let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+            rms_db(mean_square) < threshold_db
+        })
+        .collect()
+}
+
+/// Find the frame ranges `[start, end)` to keep after removing long silences
+///
+/// Silent runs shorter than `min_silence_frames` are kept untouched. Longer
+/// runs are cut down to `padding_frames` on each side of the neighbouring
+/// speech, so speech never sounds clipped. Returns the kept ranges and the
+/// number of silent regions that were shortened.
+fn speech_ranges(silent: &[bool], min_silence_frames: usize, padding_frames: usize) -> (Vec<(usize, usize)>, usize) {
+    let total = silent.len();
+    let mut kept = Vec::new();
+    let mut removed = 0;
+    let mut keep_from = 0;
+    let mut i = 0;
+
+    while i < total {
+        if !silent[i] {
+            i += 1;
+            continue;
+        }
+
+        let run_start = i;
+        while i < total && silent[i] {
+            i += 1;
+        }
+        let run_end = i;
+
+        if run_end - run_start < min_silence_frames {
+            continue;
+        }
+
+        // Keep padding next to speech, but not at the very edges of the file
+        let cut_start = if run_start == 0 { 0 } else { run_start + padding_frames };
+        let cut_end = if run_end == total { total } else { run_end.saturating_sub(padding_frames) };
+
+        if cut_end > cut_start {
+            if cut_start > keep_from {
+                kept.push((keep_from, cut_start));
+            }
+            keep_from = cut_end;
+            removed += 1;
+        }
+    }
+
+    if keep_from < total {
+        kept.push((keep_from, total));
+    }
+
+    (kept, removed)
+}
+
+/// Remove silent regions from a recording, keeping a little padding around speech
+///
+/// The audio is analysed in 10 ms frames; any run of frames quieter than
+/// `threshold_db` (dBFS) lasting at least `min_silence_ms` is removed, except
+/// for `keep_padding_ms` on either side of the surrounding speech.
+#[tauri::command]
+pub async fn remove_silences(
+    input_path: String,
+    output_path: String,
+    threshold_db: f32,
+    min_silence_ms: u32,
+    keep_padding_ms: u32,
+) -> Result<SilenceRemovalResult, String> {
+    let (spec, samples) = read_wav_samples(Path::new(&input_path))?;
+    let channels = spec.channels as usize;
+    let frame_len = ((spec.sample_rate * FRAME_MS / 1000) as usize).max(1);
+
+    let silent = silent_frames(&samples, channels, frame_len, threshold_db);
+    let min_silence_frames = (min_silence_ms / FRAME_MS).max(1) as usize;
+    let padding_frames = (keep_padding_ms / FRAME_MS) as usize;
+    let (kept, regions_removed) = speech_ranges(&silent, min_silence_frames, padding_frames);
+
+    let samples_per_frame = frame_len * channels;
+    let samples_per_ms = spec.sample_rate as f64 * channels as f64 / 1000.0;
+    let samples_to_ms = |n: usize| (n as f64 / samples_per_ms).round() as i64;
+    let mut output = Vec::with_capacity(samples.len());
+    let mut mapping = Vec::with_capacity(kept.len());
+    for (start, end) in kept {
+        let from = start * samples_per_frame;
+        let to = (end * samples_per_frame).min(samples.len());
+        mapping.push(KeptRegion {
+            original_start_ms: samples_to_ms(from),
+            trimmed_start_ms: samples_to_ms(output.len()),
+            duration_ms: samples_to_ms(to - from),
+        });
+        output.extend_from_slice(&samples[from..to]);
+    }
+
+    write_wav_samples(Path::new(&output_path), spec.sample_rate, spec.channels, &output)?;
+
+    let samples_per_second = spec.sample_rate as f64 * channels as f64;
+    let result = SilenceRemovalResult {
+        original_duration_secs: samples.len() as f64 / samples_per_second,
+        trimmed_duration_secs: output.len() as f64 / samples_per_second,
+        regions_removed,
+        mapping,
+    };
+
+    println!(
+        "[Audio Ops] remove_silences: {:.2}s -> {:.2}s, {} regions removed",
+        result.original_duration_secs, result.trimmed_duration_secs, result.regions_removed
+    );
+
+    Ok(result)
+}
+
+/// Map a time in a trimmed recording (e.g. a transcription timestamp) back to
+/// the original recording, using the `mapping` returned by `remove_silences`
+#[tauri::command]
+pub fn map_trimmed_time(trimmed_ms: i64, mapping: Vec<KeptRegion>) -> i64 {
+    map_trimmed_time_to_original(trimmed_ms, &mapping)
+}
+
+/// Input chunk length for resampling
+const RESAMPLE_CHUNK_SIZE: usize = 1024;
+
+/// Resample interleaved audio from `from_rate` to `to_rate`, one channel at a time
+///
+/// Uses the same resamplers as transcription, with the quality picked by length.
+pub(crate) fn resample_interleaved(
+    samples: &[f32],
+    channels: usize,
+    from_rate: u32,
+    to_rate: u32,
+) -> Result<Vec<f32>, String> {
+    let channels = channels.max(1);
+    let frames = samples.len() / channels;
+    let ratio = to_rate as f64 / from_rate as f64;
+    let expected_frames = (frames as f64 * ratio).round() as usize;
+    let quality = ResampleQuality::Auto.resolve(frames, from_rate);
+
+    let mut resampled_channels = Vec::with_capacity(channels);
+    for channel in 0..channels {
+        let input: Vec<f32> = samples.iter().skip(channel).step_by(channels).copied().collect();
+        let mut resampler = build_resampler(quality, ratio, RESAMPLE_CHUNK_SIZE)
+            .map_err(|e| format!("Failed to create resampler: {}", e))?;
+
+        let mut output = Vec::with_capacity(expected_frames);
+        for chunk in input.chunks(RESAMPLE_CHUNK_SIZE) {
+            // The resampler takes fixed-size chunks; pad the last one with silence
+            let mut chunk = chunk.to_vec();
+            chunk.resize(RESAMPLE_CHUNK_SIZE, 0.0);
+            let waves_out = resampler
+                .process(&[chunk], None)
+                .map_err(|e| format!("Resampling failed: {}", e))?;
+            output.extend_from_slice(&waves_out[0]);
+        }
+        // Drop what the padding produced
+        output.resize(expected_frames, 0.0);
+        resampled_channels.push(output);
+    }
+
+    let mut interleaved = Vec::with_capacity(expected_frames * channels);
+    for frame in 0..expected_frames {
+        interleaved.extend(resampled_channels.iter().map(|channel| channel[frame]));
+    }
+    Ok(interleaved)
+}
+
+/// Join WAV files end to end into a new WAV file
+///
+/// All inputs must have the same channel count. Files at a different sample
+/// rate than the first are resampled to match it. The output is written with
+/// the recorder's `WavWriter` (32-bit float), one input at a time.
+#[tauri::command]
+pub async fn concatenate_wav_files(
+    input_paths: Vec<String>,
+    output_path: String,
+) -> Result<AudioInfo, String> {
+    let Some(first_path) = input_paths.first() else {
+        return Err("No input files to concatenate".to_string());
+    };
+    let output = PathBuf::from(&output_path);
+    if input_paths.iter().any(|path| Path::new(path) == output) {
+        return Err(format!("Output file {:?} is also an input", output));
+    }
+
+    // Check every header before writing anything
+    let first_spec = hound::WavReader::open(first_path)
+        .map_err(|e| format!("Failed to open WAV file {:?}: {}", first_path, e))?
+        .spec();
+    for path in &input_paths[1..] {
+        let spec = hound::WavReader::open(path)
+            .map_err(|e| format!("Failed to open WAV file {:?}: {}", path, e))?
+            .spec();
+        if spec.channels != first_spec.channels {
+            return Err(format!(
+                "{:?} has {} channels but {:?} has {}",
+                path, spec.channels, first_path, first_spec.channels
+            ));
+        }
+    }
+
+    let (sample_rate, channels) = (first_spec.sample_rate, first_spec.channels);
+    let config = WavWriterConfig::default();
+    let mut writer = WavWriter::new(output.clone(), sample_rate, channels, config)
+        .map_err(|e| format!("Failed to create WAV file {:?}: {}", output, e))?;
+
+    for path in &input_paths {
+        let (spec, mut samples) = read_wav_samples(Path::new(path))?;
+        if spec.sample_rate != sample_rate {
+            println!(
+                "[Audio Ops] concatenate_wav_files: resampling {} from {} Hz to {} Hz",
+                path, spec.sample_rate, sample_rate
+            );
+            samples =
+                resample_interleaved(&samples, channels as usize, spec.sample_rate, sample_rate)?;
+        }
+        writer
+            .write_samples_f32(&samples)
+            .map_err(|e| format!("Failed to write samples: {}", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("Failed to finalize WAV: {}", e))?;
+
+    let duration_secs = writer.get_duration_seconds() as f64;
+    println!(
+        "[Audio Ops] concatenate_wav_files: {} files -> {} ({:.2}s)",
+        input_paths.len(),
+        output_path,
+        duration_secs
+    );
+
+    Ok(AudioInfo {
+        duration_secs,
+        sample_rate,
+        channels,
+        bits_per_sample: 32,
+        format: "wav".to_string(),
+    })
+}
+
+/// Write frames `start..end` of interleaved samples to a new WAV file
+fn write_frame_range(
+    path: &Path,
+    spec: hound::WavSpec,
+    samples: &[f32],
+    start: usize,
+    end: usize,
+) -> Result<AudioInfo, String> {
+    let channels = spec.channels as usize;
+    let range = &samples[start * channels..end * channels];
+    write_wav_samples(path, spec.sample_rate, spec.channels, range)?;
+    Ok(AudioInfo {
+        duration_secs: (end - start) as f64 / spec.sample_rate as f64,
+        sample_rate: spec.sample_rate,
+        channels: spec.channels,
+        bits_per_sample: 32,
+        format: "wav".to_string(),
+    })
+}
+
+/// Cut the audio between `start_secs` and `end_secs` out of a WAV file into
+/// a new one
+///
+/// A negative start is treated as 0 and an end past the end of the file as
+/// the end of the file.
+#[tauri::command]
+pub async fn trim_wav_file(
+    input_path: String,
+    output_path: String,
+    start_secs: f64,
+    end_secs: f64,
+) -> Result<AudioInfo, String> {
+    let (spec, samples) = read_wav_samples(Path::new(&input_path))?;
+    let frames = samples.len() / (spec.channels as usize).max(1);
+    let to_frame =
+        |secs: f64| ((secs.max(0.0) * spec.sample_rate as f64).round() as usize).min(frames);
+    let (start, end) = (to_frame(start_secs), to_frame(end_secs));
+    if end <= start {
+        return Err(format!(
+            "Nothing to keep between {}s and {}s of {:?}",
+            start_secs, end_secs, input_path
+        ));
+    }
+
+    let info = write_frame_range(Path::new(&output_path), spec, &samples, start, end)?;
+    println!(
+        "[Audio Ops] trim_wav_file: {} [{:.2}s, {:.2}s) -> {}",
+        input_path,
+        start as f64 / spec.sample_rate as f64,
+        end as f64 / spec.sample_rate as f64,
+        output_path
+    );
+    Ok(info)
+}
+
+/// Split a WAV file at the given times into consecutive segments in `output_dir`
+///
+/// Segments are named after the input file, e.g. `talk_001.wav`, and their
+/// paths are returned in order. Split points outside the recording are
+/// ignored, so N valid points give N + 1 segments.
+#[tauri::command]
+pub async fn split_wav_at_timestamps(
+    input_path: String,
+    split_secs: Vec<f64>,
+    output_dir: String,
+) -> Result<Vec<String>, String> {
+    let input = Path::new(&input_path);
+    let (spec, samples) = read_wav_samples(input)?;
+    let frames = samples.len() / (spec.channels as usize).max(1);
+
+    let mut boundaries: Vec<usize> = split_secs
+        .iter()
+        .map(|secs| (secs * spec.sample_rate as f64).round())
+        .filter(|&frame| frame > 0.0 && frame < frames as f64)
+        .map(|frame| frame as usize)
+        .collect();
+    boundaries.sort_unstable();
+    boundaries.dedup();
+    boundaries.insert(0, 0);
+    boundaries.push(frames);
+
+    let output_dir = PathBuf::from(output_dir);
+    std::fs::create_dir_all(&output_dir)
+        .map_err(|e| format!("Failed to create output folder {:?}: {}", output_dir, e))?;
+    let stem = input
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "segment".to_string());
+
+    let mut outputs = Vec::with_capacity(boundaries.len() - 1);
+    for (index, range) in boundaries.windows(2).enumerate() {
+        let path = output_dir.join(format!("{}_{:03}.wav", stem, index + 1));
+        write_frame_range(&path, spec, &samples, range[0], range[1])?;
+        outputs.push(path.to_string_lossy().to_string());
+    }
+
+    println!(
+        "[Audio Ops] split_wav_at_timestamps: {} -> {} segments in {:?}",
+        input_path,
+        outputs.len(),
+        output_dir
+    );
+    Ok(outputs)
+}
+
+/// Step between the overlapping analysis frames used to find silent regions
+const SILENCE_HOP_MS: u32 = FRAME_MS / 2;
+
+/// A stretch of a recording quieter than the silence threshold - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SilenceRegion {
+    pub start_ms: u64,
+    pub end_ms: u64,
+}
+
+/// Find the silent frame ranges `[start, end)` in interleaved samples
+///
+/// The RMS is measured over 10 ms frames that overlap by half; a region is a
+/// run of frames quieter than `threshold_db` (dBFS) lasting at least
+/// `min_silence_ms`.
+fn silent_regions(
+    samples: &[f32],
+    spec: hound::WavSpec,
+    threshold_db: f32,
+    min_silence_ms: u32,
+) -> Vec<(usize, usize)> {
+    let channels = (spec.channels as usize).max(1);
+    let frames = samples.len() / channels;
+    let frame_len = ((spec.sample_rate * FRAME_MS / 1000) as usize).max(1);
+    let hop_len = ((spec.sample_rate * SILENCE_HOP_MS / 1000) as usize).max(1);
+    let min_hops = min_silence_ms.div_ceil(SILENCE_HOP_MS).max(1) as usize;
+
+    let silent: Vec<bool> = (0..frames)
+        .step_by(hop_len)
+        .map(|start| {
+            let window = &samples[start * channels..(start + frame_len).min(frames) * channels];
+            let mean_square = window.iter().map(|s| s * s).sum::<f32>() / window.len() as f32;
+            rms_db(mean_square) < threshold_db
+        })
+        .collect();
+
+    let mut regions = Vec::new();
+    let mut i = 0;
+    while i < silent.len() {
+        if !silent[i] {
+            i += 1;
+            continue;
+        }
+        let run_start = i;
+        while i < silent.len() && silent[i] {
+            i += 1;
+        }
+        if i - run_start >= min_hops {
+            regions.push((run_start * hop_len, (i * hop_len).min(frames)));
+        }
+    }
+    regions
+}
+
+/// Remove leading and trailing silence from a WAV file, writing the rest to
+/// `output_path`
+///
+/// Silence inside the recording is left alone. Fails if the whole file is
+/// silent.
+#[tauri::command]
+pub async fn trim_silence(
+    input_path: String,
+    output_path: String,
+    threshold_dbfs: f32,
+    min_silence_ms: u32,
+) -> Result<AudioInfo, String> {
+    let (spec, samples) = read_wav_samples(Path::new(&input_path))?;
+    let frames = samples.len() / (spec.channels as usize).max(1);
+    let regions = silent_regions(&samples, spec, threshold_dbfs, min_silence_ms);
+
+    let start = match regions.first() {
+        Some(&(0, end)) => end,
+        _ => 0,
+    };
+    let end = match regions.last() {
+        Some(&(start, end)) if end == frames => start,
+        _ => frames,
+    };
+    if end <= start {
+        return Err(format!("No audio above {} dBFS in {:?}", threshold_dbfs, input_path));
+    }
+
+    let info = write_frame_range(Path::new(&output_path), spec, &samples, start, end)?;
+    println!(
+        "[Audio Ops] trim_silence: {:.2}s -> {:.2}s",
+        frames as f64 / spec.sample_rate as f64,
+        info.duration_secs
+    );
+    Ok(info)
+}
+
+/// List every silent region in a WAV file, e.g. to show voice activity in the UI
+#[tauri::command]
+pub async fn detect_silence_regions(
+    input_path: String,
+    threshold_dbfs: f32,
+    min_silence_ms: u32,
+) -> Result<Vec<SilenceRegion>, String> {
+    let (spec, samples) = read_wav_samples(Path::new(&input_path))?;
+    let frames_to_ms = |frames: usize| frames as u64 * 1000 / spec.sample_rate as u64;
+
+    Ok(silent_regions(&samples, spec, threshold_dbfs, min_silence_ms)
+        .into_iter()
+        .map(|(start, end)| SilenceRegion {
+            start_ms: frames_to_ms(start),
+            end_ms: frames_to_ms(end),
+        })
+        .collect())
+}
+
+/// Length of the overlap-add windows used for time-stretching
+const STRETCH_WINDOW_MS: u32 = 20;
+
+/// Range of speed factors `time_stretch_wav` accepts
+const MIN_SPEED_FACTOR: f32 = 0.5;
+const MAX_SPEED_FACTOR: f32 = 4.0;
+
+/// Change the duration of one channel without changing its pitch, using
+/// overlap-add
+///
+/// Hann windows are read every `window_len / 2 * speed_factor` samples and
+/// written every `window_len / 2`, then normalized by the summed window.
+fn overlap_add_stretch(input: &[f32], window_len: usize, speed_factor: f32) -> Vec<f32> {
+    let window_len = window_len.max(2);
+    let synthesis_hop = window_len / 2;
+    let analysis_hop = ((synthesis_hop as f32 * speed_factor).round() as usize).max(1);
+    let output_len = (input.len() as f64 / speed_factor as f64).round() as usize;
+
+    let window: Vec<f32> = (0..window_len)
+        .map(|n| {
+            let phase = 2.0 * std::f32::consts::PI * n as f32 / window_len as f32;
+            0.5 - 0.5 * phase.cos()
+        })
+        .collect();
+
+    let mut output = vec![0.0f32; output_len + window_len];
+    let mut weights = vec![0.0f32; output_len + window_len];
+    let mut read = 0;
+    let mut write = 0;
+    while read < input.len() && write < output_len {
+        for (n, &w) in window.iter().enumerate() {
+            let sample = input.get(read + n).copied().unwrap_or(0.0);
+            output[write + n] += sample * w;
+            weights[write + n] += w;
+        }
+        read += analysis_hop;
+        write += synthesis_hop;
+    }
+
+    output.truncate(output_len);
+    for (sample, &weight) in output.iter_mut().zip(&weights) {
+        // Only the very edges have near-zero weight; leave them quiet
+        if weight > 1e-3 {
+            *sample /= weight;
+        }
+    }
+    output
+}
+
+/// Speed a WAV file up or slow it down without changing its pitch
+///
+/// `speed_factor` must be between 0.5 and 4.0; 1.5 plays back in two thirds
+/// of the time. Uses overlap-add with 20 ms windows at 50% overlap.
+#[tauri::command]
+pub async fn time_stretch_wav(
+    input_path: String,
+    output_path: String,
+    speed_factor: f32,
+) -> Result<(), String> {
+    if !(MIN_SPEED_FACTOR..=MAX_SPEED_FACTOR).contains(&speed_factor) {
+        return Err(format!(
+            "Speed factor must be between {} and {}, got {}",
+            MIN_SPEED_FACTOR, MAX_SPEED_FACTOR, speed_factor
+        ));
+    }
+
+    let (spec, samples) = read_wav_samples(Path::new(&input_path))?;
+    let channels = (spec.channels as usize).max(1);
+    let window_len = (spec.sample_rate * STRETCH_WINDOW_MS / 1000) as usize;
+
+    let stretched: Vec<Vec<f32>> = (0..channels)
+        .map(|channel| {
+            let input: Vec<f32> = samples.iter().skip(channel).step_by(channels).copied().collect();
+            overlap_add_stretch(&input, window_len, speed_factor)
+        })
+        .collect();
+    let frames = stretched.first().map_or(0, Vec::len);
+    let mut output = Vec::with_capacity(frames * channels);
+    for frame in 0..frames {
+        output.extend(stretched.iter().map(|channel| channel[frame]));
+    }
+
+    write_wav_samples(Path::new(&output_path), spec.sample_rate, spec.channels, &output)?;
+
+    println!(
+        "[Audio Ops] time_stretch_wav: {:.2}s -> {:.2}s at {}x",
+        samples.len() as f64 / channels as f64 / spec.sample_rate as f64,
+        frames as f64 / spec.sample_rate as f64,
+        speed_factor
+    );
+    Ok(())
+}
+
+/// Peak absolute amplitude of interleaved samples in `buckets` equal windows
+///
+/// Channels are mixed to mono first. Windows that hold no samples (more
+/// buckets than frames) are 0.0.
+pub(crate) fn waveform_peaks(samples: &[f32], channels: usize, buckets: usize) -> Vec<f32> {
+    let mut peaks = vec![0.0f32; buckets];
+    let channels = channels.max(1);
+    let frames = samples.len() / channels;
+    if frames == 0 || buckets == 0 {
+        return peaks;
+    }
+
+    for (i, frame) in samples.chunks_exact(channels).enumerate() {
+        let mono = frame.iter().sum::<f32>() / channels as f32;
+        let bucket = i * buckets / frames;
+        peaks[bucket] = peaks[bucket].max(mono.abs());
+    }
+    peaks
+}
+
+/// Compute waveform peaks for drawing a recording: one peak absolute amplitude
+/// per bucket, in [0.0, 1.0]
+///
+/// Pass `samples` (mono, e.g. from an earlier transcription of the same file)
+/// to skip reading the file again; `file_path` is then only used in logs.
+#[tauri::command]
+pub async fn compute_waveform(
+    file_path: String,
+    buckets: u32,
+    samples: Option<Vec<f32>>,
+) -> Result<Vec<f32>, String> {
+    let peaks = match samples {
+        Some(samples) => waveform_peaks(&samples, 1, buckets as usize),
+        None => {
+            let (spec, samples) = read_wav_samples(Path::new(&file_path))?;
+            waveform_peaks(&samples, spec.channels as usize, buckets as usize)
+        }
+    };
+
+    println!("[Audio Ops] compute_waveform: {} buckets for {}", peaks.len(), file_path);
+    Ok(peaks)
+}
+
+/// Most frames `compute_spectrogram` returns; longer files are subsampled in time
+const MAX_SPECTROGRAM_FRAMES: usize = 10_000;
+
+/// Magnitude spectrogram of a recording - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpectrogramData {
+    /// One magnitude spectrum per frame, `num_bins` values from 0 Hz up to
+    /// the Nyquist frequency
+    pub frames: Vec<Vec<f32>>,
+    pub num_bins: u32,
+    pub sample_rate: u32,
+    /// Samples between the starts of consecutive frames, after any subsampling
+    pub hop_size: u32,
+}
+
+/// Compute a magnitude spectrogram of a WAV file for drawing
+///
+/// Channels are mixed to mono and each `fft_size` window, `hop_size` samples
+/// apart, is Hann-windowed before its FFT. Magnitudes are scaled so a
+/// full-scale sine peaks near 1.0. Past 10000 frames, frames are skipped
+/// evenly and `hop_size` in the result grows to match.
+#[tauri::command]
+pub async fn compute_spectrogram(
+    file_path: String,
+    fft_size: u32,
+    hop_size: u32,
+) -> Result<SpectrogramData, String> {
+    if fft_size < 2 || hop_size == 0 {
+        return Err(format!(
+            "Invalid spectrogram size: fft_size {} (must be at least 2), hop_size {}",
+            fft_size, hop_size
+        ));
+    }
+
+    let (spec, samples) = read_wav_samples(Path::new(&file_path))?;
+    let channels = (spec.channels as usize).max(1);
+    let mono: Vec<f32> = samples
+        .chunks(channels)
+        .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+        .collect();
+
+    let fft_size = fft_size as usize;
+    let total_frames = mono.len().saturating_sub(fft_size) / hop_size as usize + 1;
+    let stride = total_frames.div_ceil(MAX_SPECTROGRAM_FRAMES);
+    let hop = hop_size as usize * stride;
+
+    let window: Vec<f32> = (0..fft_size)
+        .map(|n| {
+            let phase = 2.0 * std::f32::consts::PI * n as f32 / fft_size as f32;
+            0.5 - 0.5 * phase.cos()
+        })
+        .collect();
+    let scale = 2.0 / window.iter().sum::<f32>();
+
+    let fft = realfft::RealFftPlanner::<f32>::new().plan_fft_forward(fft_size);
+    let mut input = fft.make_input_vec();
+    let mut spectrum = fft.make_output_vec();
+    let mut frames = Vec::with_capacity(total_frames.div_ceil(stride));
+    for start in (0..total_frames).map(|frame| frame * hop_size as usize).step_by(stride) {
+        for (n, value) in input.iter_mut().enumerate() {
+            // Short files get a single zero-padded frame
+            *value = mono.get(start + n).copied().unwrap_or(0.0) * window[n];
+        }
+        fft.process(&mut input, &mut spectrum).map_err(|e| format!("FFT failed: {}", e))?;
+        frames.push(spectrum.iter().map(|bin| bin.norm() * scale).collect());
+    }
+
+    println!(
+        "[Audio Ops] compute_spectrogram: {} frames of {} bins for {}",
+        frames.len(),
+        spectrum.len(),
+        file_path
+    );
+    Ok(SpectrogramData {
+        frames,
+        num_bins: spectrum.len() as u32,
+        sample_rate: spec.sample_rate,
+        hop_size: hop as u32,
+    })
+}
@@ -1,5 +1,10 @@
-use std::process::{Command, Stdio};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
 use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
 
 #[cfg(target_os = "windows")]
 use std::os::windows::process::CommandExt;
@@ -8,6 +13,12 @@ use std::os::windows::process::CommandExt;
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+/// Event emitted for each line a streaming command prints
+pub const COMMAND_OUTPUT_LINE_EVENT: &str = "command_output_line";
+
+/// Event emitted once a streaming command has exited
+pub const COMMAND_OUTPUT_DONE_EVENT: &str = "command_output_done";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CommandOutput {
@@ -15,17 +26,45 @@ pub struct CommandOutput {
     pub signal: Option<i32>,
     pub stdout: String,
     pub stderr: String,
+    /// The process was killed for running past `timeout_ms`
+    #[serde(default)]
+    pub timed_out: bool,
+}
+
+/// Which pipe a line of output came from
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+/// A line of output from a streaming command - emitted to frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandOutputLine {
+    pub event_id: String,
+    pub line: String,
+    pub stream: OutputStream,
+}
+
+/// A streaming command has exited - emitted to frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandOutputDone {
+    pub event_id: String,
+    /// `None` if the process was killed by a signal or couldn't be waited on
+    pub code: Option<i32>,
 }
 
 /// Parse a command string into program and arguments.
 /// Handles quoted arguments properly for direct execution without shell wrapper.
+///
+/// Both double- and single-quoted sections are kept together and their quotes
+/// removed, as a shell would. An unmatched quote takes the rest of the string
+/// as part of its token instead of failing.
 fn parse_command(command: &str) -> (String, Vec<String>) {
-    // Match quoted strings or non-space sequences
-    let re = regex::Regex::new(r#"(?:[^\s"]+|"[^"]*")+"#).unwrap();
-    let parts: Vec<String> = re
-        .find_iter(command)
-        .map(|m| m.as_str().trim_matches('"').to_string())
-        .collect();
+    // Match quoted strings or non-space sequences; an unclosed quote runs to the end
+    let re = regex::Regex::new(r#"(?s)(?:[^\s"']+|"[^"]*"|'[^']*'|["'].*)+"#).unwrap();
+    let parts: Vec<String> = re.find_iter(command).map(|m| unquote(m.as_str())).collect();
 
     if parts.is_empty() {
         return (String::new(), Vec::new());
@@ -34,6 +73,107 @@ fn parse_command(command: &str) -> (String, Vec<String>) {
     (parts[0].clone(), parts[1..].to_vec())
 }
 
+/// Remove the quotes delimiting quoted sections of a token, keeping their contents
+fn unquote(token: &str) -> String {
+    let mut unquoted = String::with_capacity(token.len());
+    let mut quote = None;
+    for c in token.chars() {
+        match quote {
+            Some(open) if c == open => quote = None,
+            None if c == '"' || c == '\'' => quote = Some(c),
+            _ => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+
+/// How long to keep reading a killed process's output pipes
+///
+/// A grandchild that inherited the pipes can hold them open long after the
+/// process itself was killed, so the readers are abandoned after this.
+const KILLED_PIPE_GRACE: Duration = Duration::from_millis(500);
+
+/// Output read so far from a child's pipe by a background thread
+struct PipeReader {
+    handle: JoinHandle<()>,
+    output: Arc<Mutex<Vec<u8>>>,
+}
+
+impl PipeReader {
+    /// Wait for the pipe to close and return everything read from it
+    fn join(self) -> Vec<u8> {
+        let _ = self.handle.join();
+        self.take_output()
+    }
+
+    /// Wait for the pipe to close, but no later than `deadline`; after that the
+    /// thread is left to finish on its own and what was read so far is returned
+    fn join_until(self, deadline: Instant) -> Vec<u8> {
+        while !self.handle.is_finished() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        self.take_output()
+    }
+
+    fn take_output(&self) -> Vec<u8> {
+        self.output
+            .lock()
+            .map(|mut output| std::mem::take(&mut *output))
+            .unwrap_or_default()
+    }
+}
+
+/// Read a child's output pipe to the end on its own thread, so a chatty
+/// process can't deadlock against us
+fn read_pipe<R: Read + Send + 'static>(pipe: Option<R>) -> PipeReader {
+    let output = Arc::new(Mutex::new(Vec::new()));
+    let thread_output = output.clone();
+    let handle = std::thread::spawn(move || {
+        let Some(mut pipe) = pipe else {
+            return;
+        };
+        let mut chunk = [0u8; 8192];
+        while let Ok(n) = pipe.read(&mut chunk) {
+            if n == 0 {
+                break;
+            }
+            if let Ok(mut output) = thread_output.lock() {
+                output.extend_from_slice(&chunk[..n]);
+            }
+        }
+    });
+    PipeReader { handle, output }
+}
+
+/// Write `data` to a child's stdin on its own thread, then close it so the
+/// process sees EOF
+fn write_stdin<W: Write + Send + 'static>(pipe: Option<W>, data: Vec<u8>) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.write_all(&data);
+        }
+    })
+}
+
+/// Wait for a child to exit, killing it if it runs past `timeout`
+///
+/// Returns `None` if the child was killed. `Child::kill` sends SIGKILL on Unix
+/// and calls `TerminateProcess` on Windows.
+fn wait_with_timeout(child: &mut Child, timeout: Duration) -> std::io::Result<Option<ExitStatus>> {
+    let started = Instant::now();
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if started.elapsed() >= timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
 /// Execute a command and wait for it to complete.
 ///
 /// Parses the command string into program and arguments, then executes directly
@@ -45,8 +185,18 @@ fn parse_command(command: &str) -> (String, Vec<String>) {
 ///
 /// On Windows, also uses CREATE_NO_WINDOW flag to prevent console window flash (GitHub issue #815).
 ///
+/// With `timeout_ms`, the process is killed if it hasn't exited in time (e.g.
+/// an FFmpeg invocation stuck waiting for stdin) and the output has
+/// `timed_out` set, no exit code, and whatever it printed before being killed.
+///
+/// With `stdin_data`, the bytes are written to the process's stdin, which is
+/// then closed; e.g. `ffmpeg -i pipe:0 ...` can convert audio without a temp
+/// file. Without it, stdin is empty.
+///
 /// # Arguments
 /// * `command` - The command to execute as a string
+/// * `timeout_ms` - Optional limit on how long the process may run
+/// * `stdin_data` - Optional bytes to send to the process's stdin
 ///
 /// # Returns
 /// Result containing the command output (stdout, stderr, exit code) or error message
@@ -57,7 +207,11 @@ fn parse_command(command: &str) -> (String, Vec<String>) {
 /// execute_command("ffmpeg -i input.wav output.mp3".to_string())
 /// ```
 #[tauri::command]
-pub async fn execute_command(command: String) -> Result<CommandOutput, String> {
+pub async fn execute_command(
+    command: String,
+    timeout_ms: Option<u64>,
+    stdin_data: Option<Vec<u8>>,
+) -> Result<CommandOutput, String> {
     let (program, args) = parse_command(&command);
 
     if program.is_empty() {
@@ -76,6 +230,10 @@ pub async fn execute_command(command: String) -> Result<CommandOutput, String> {
         println!("[Rust] execute_command: Windows - using CREATE_NO_WINDOW flag");
     }
 
+    if timeout_ms.is_some() || stdin_data.is_some() {
+        return execute_spawned(cmd, stdin_data, timeout_ms.map(Duration::from_millis));
+    }
+
     match cmd.output() {
         Ok(output) => {
             let result = CommandOutput {
@@ -83,6 +241,7 @@ pub async fn execute_command(command: String) -> Result<CommandOutput, String> {
                 signal: None, // Signal is Unix-specific, not available from std::process::Output
                 stdout: String::from_utf8_lossy(&output.stdout).to_string(),
                 stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+                timed_out: false,
             };
             println!("[Rust] execute_command: completed with code={:?}", result.code);
             Ok(result)
@@ -95,6 +254,157 @@ pub async fn execute_command(command: String) -> Result<CommandOutput, String> {
     }
 }
 
+/// Run a prepared command, feeding it `stdin_data` and killing it after `timeout`
+fn execute_spawned(
+    mut cmd: Command,
+    stdin_data: Option<Vec<u8>>,
+    timeout: Option<Duration>,
+) -> Result<CommandOutput, String> {
+    // Match `Command::output`, which gives the process an empty stdin
+    cmd.stdin(if stdin_data.is_some() { Stdio::piped() } else { Stdio::null() });
+
+    let mut child = cmd.spawn().map_err(|e| {
+        let error_msg = format!("Command execution failed: {}", e);
+        println!("[Rust] execute_command: error - {}", error_msg);
+        error_msg
+    })?;
+
+    // Written on its own thread so a process that answers before reading all
+    // its input can't deadlock against us
+    let writer = stdin_data.map(|data| write_stdin(child.stdin.take(), data));
+    let stdout_reader = read_pipe(child.stdout.take());
+    let stderr_reader = read_pipe(child.stderr.take());
+    let status = match timeout {
+        Some(timeout) => wait_with_timeout(&mut child, timeout),
+        None => child.wait().map(Some),
+    }
+    .map_err(|e| format!("Failed to wait for command: {}", e))?;
+
+    let (stdout, stderr) = if status.is_some() {
+        if let Some(writer) = writer {
+            let _ = writer.join();
+        }
+        (stdout_reader.join(), stderr_reader.join())
+    } else {
+        // Killed: don't wait on pipes (or a stdin write) a grandchild may still hold
+        let deadline = Instant::now() + KILLED_PIPE_GRACE;
+        (stdout_reader.join_until(deadline), stderr_reader.join_until(deadline))
+    };
+
+    let result = CommandOutput {
+        code: status.and_then(|status| status.code()),
+        signal: None,
+        stdout: String::from_utf8_lossy(&stdout).to_string(),
+        stderr: String::from_utf8_lossy(&stderr).to_string(),
+        timed_out: status.is_none(),
+    };
+
+    if result.timed_out {
+        println!("[Rust] execute_command: killed after {:?}", timeout.unwrap_or_default());
+    } else {
+        println!("[Rust] execute_command: completed with code={:?}", result.code);
+    }
+    Ok(result)
+}
+
+/// Emit each line of a child's output pipe as a `command_output_line` event
+fn stream_lines<R: Read + Send + 'static>(
+    pipe: Option<R>,
+    stream: OutputStream,
+    event_id: String,
+    app_handle: AppHandle,
+) -> JoinHandle<()> {
+    std::thread::spawn(move || {
+        let Some(pipe) = pipe else {
+            return;
+        };
+        let mut reader = BufReader::new(pipe);
+        let mut buf = Vec::new();
+        // Read raw bytes so output that isn't valid UTF-8 doesn't end the stream
+        while matches!(reader.read_until(b'\n', &mut buf), Ok(n) if n > 0) {
+            let line = String::from_utf8_lossy(&buf).trim_end_matches(['\r', '\n']).to_string();
+            let _ = app_handle.emit(
+                COMMAND_OUTPUT_LINE_EVENT,
+                CommandOutputLine {
+                    event_id: event_id.clone(),
+                    line,
+                    stream,
+                },
+            );
+            buf.clear();
+        }
+    })
+}
+
+/// Spawn a command and stream its output to the frontend line by line.
+///
+/// Parsed and spawned like `spawn_command`. Each line printed to stdout or
+/// stderr is emitted as a `command_output_line` event, and a
+/// `command_output_done` event with the exit code follows once the process
+/// exits. Every event carries `event_id`, so the caller can tell concurrent
+/// commands apart. Stdin is empty.
+///
+/// # Arguments
+/// * `command` - The command to spawn as a string
+/// * `event_id` - Caller-chosen ID attached to this command's events
+///
+/// # Returns
+/// Result containing the process ID or error message
+#[tauri::command]
+pub async fn execute_command_streaming(
+    command: String,
+    event_id: String,
+    app_handle: AppHandle,
+) -> Result<u32, String> {
+    let (program, args) = parse_command(&command);
+
+    if program.is_empty() {
+        return Err("Empty command".to_string());
+    }
+
+    println!("[Rust] execute_command_streaming: program='{}', args={:?}", program, args);
+
+    let mut cmd = Command::new(&program);
+    cmd.args(&args);
+    cmd.stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = cmd.spawn().map_err(|e| {
+        let error_msg = format!("Failed to spawn process: {}", e);
+        println!("[Rust] execute_command_streaming: error - {}", error_msg);
+        error_msg
+    })?;
+    let pid = child.id();
+
+    let stdout_streamer = stream_lines(
+        child.stdout.take(),
+        OutputStream::Stdout,
+        event_id.clone(),
+        app_handle.clone(),
+    );
+    let stderr_streamer = stream_lines(
+        child.stderr.take(),
+        OutputStream::Stderr,
+        event_id.clone(),
+        app_handle.clone(),
+    );
+
+    std::thread::spawn(move || {
+        let code = child.wait().ok().and_then(|status| status.code());
+        // Let the last lines go out before announcing the exit
+        let _ = stdout_streamer.join();
+        let _ = stderr_streamer.join();
+        println!("[Rust] execute_command_streaming: {} exited with code={:?}", pid, code);
+        let _ = app_handle.emit(COMMAND_OUTPUT_DONE_EVENT, CommandOutputDone { event_id, code });
+    });
+
+    Ok(pid)
+}
+
 /// Spawn a child process without waiting for it to complete.
 ///
 /// Parses the command string into program and arguments, then spawns directly
@@ -149,3 +459,99 @@ pub async fn spawn_command(command: String) -> Result<u32, String> {
         }
     }
 }
+
+/// Run a command with `input` on stdin and return its stdout
+///
+/// Parsed and spawned like `execute_command` (no shell, no console window on
+/// Windows). The process is killed if it hasn't exited within `timeout`, and
+/// a non-zero exit status is an error.
+pub(crate) fn pipe_through_command(
+    command: &str,
+    input: &str,
+    timeout: Duration,
+) -> Result<String, String> {
+    let (program, args) = parse_command(command);
+
+    if program.is_empty() {
+        return Err("Empty command".to_string());
+    }
+
+    let mut cmd = Command::new(&program);
+    cmd.args(&args);
+    cmd.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("Failed to spawn '{}': {}", program, e))?;
+
+    // Feed stdin and drain the output pipes on their own threads so a chatty
+    // process can't deadlock against us
+    let writer = write_stdin(child.stdin.take(), input.as_bytes().to_vec());
+    let stdout_reader = read_pipe(child.stdout.take());
+    let stderr_reader = read_pipe(child.stderr.take());
+
+    let status = wait_with_timeout(&mut child, timeout)
+        .map_err(|e| format!("Failed to wait for '{}': {}", program, e))?
+        .ok_or_else(|| format!("'{}' timed out after {:?}", program, timeout))?;
+
+    let _ = writer.join();
+    let stdout = stdout_reader.join();
+    let stderr = stderr_reader.join();
+
+    if !status.success() {
+        return Err(format!(
+            "'{}' exited with {}: {}",
+            program,
+            status,
+            String::from_utf8_lossy(&stderr).trim()
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(command: &str) -> Vec<String> {
+        let (program, args) = parse_command(command);
+        assert_eq!(program, "cmd");
+        args
+    }
+
+    #[test]
+    fn parses_single_quoted_arguments() {
+        assert_eq!(args("cmd 'hello world' plain"), ["hello world", "plain"]);
+        assert_eq!(args(r#"cmd 'say "hi"'"#), [r#"say "hi""#]);
+    }
+
+    #[test]
+    fn parses_mixed_quote_styles() {
+        assert_eq!(
+            args(r#"cmd "it's" --flag='a b' pre"mid dle"post"#),
+            ["it's", "--flag=a b", "premid dlepost"]
+        );
+    }
+
+    #[test]
+    fn unterminated_quote_takes_the_rest() {
+        assert_eq!(
+            args(r#"cmd "open quote --not-a-flag"#),
+            ["open quote --not-a-flag"]
+        );
+        assert_eq!(args(r#"cmd done "half 'way"#), ["done", "half 'way"]);
+        assert_eq!(args("cmd 'left open"), ["left open"]);
+    }
+
+    #[test]
+    fn empty_quotes_are_empty_arguments() {
+        assert_eq!(args(r#"cmd '' "" x"#), ["", "", "x"]);
+        assert_eq!(parse_command("   "), (String::new(), Vec::new()));
+    }
+}
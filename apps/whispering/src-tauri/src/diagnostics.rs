@@ -0,0 +1,99 @@
+use crate::recorder::commands::AppData;
+use crate::recorder::wav_writer::WavWriterStatus;
+use crate::transcription::ffmpeg::{check_ffmpeg_available, FfmpegInfo};
+use crate::transcription::{LoadedModelInfo, ModelManager};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+/// Everything support needs to reproduce a bug report
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DiagnosticReport {
+    app_name: String,
+    app_version: String,
+    os: &'static str,
+    arch: &'static str,
+    ffmpeg: Option<FfmpegInfo>,
+    input_devices: Vec<String>,
+    /// Why the devices couldn't be listed, if they couldn't
+    input_device_error: Option<String>,
+    loaded_models: Vec<LoadedModelInfo>,
+    sessions: Vec<SessionDiagnostics>,
+}
+
+/// State of one recording session
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionDiagnostics {
+    session_id: String,
+    /// Set while recording or paused
+    recording_id: Option<String>,
+    device_error: Option<String>,
+    /// `None` until the session is initialized
+    writer: Option<WavWriterStatus>,
+}
+
+/// List input devices on the default host through the default session
+fn list_input_devices(state: &AppData) -> Result<Vec<String>, String> {
+    let session = state.session(None)?;
+    let recorder = session
+        .lock()
+        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    recorder.enumerate_devices(None)
+}
+
+/// Collect the state of every recording session
+fn session_diagnostics(state: &AppData) -> Vec<SessionDiagnostics> {
+    let session_ids = state.session_ids().unwrap_or_default();
+    session_ids
+        .into_iter()
+        .filter_map(|session_id| {
+            let session = state.session(Some(&session_id)).ok()?;
+            let recorder = session.lock().ok()?;
+            Some(SessionDiagnostics {
+                recording_id: recorder.get_current_recording_id(),
+                device_error: recorder.get_device_error(),
+                writer: recorder.get_writer_status(),
+                session_id,
+            })
+        })
+        .collect()
+}
+
+/// Build a JSON report of the app, platform, FFmpeg, audio devices, loaded
+/// models and recording sessions, to attach to bug reports
+///
+/// Parts that can't be read are left empty rather than failing the report.
+/// Logs aren't included, since they go to stdout rather than a buffer.
+#[tauri::command]
+pub async fn generate_diagnostic_report(app_handle: AppHandle) -> Result<String, String> {
+    let ffmpeg = check_ffmpeg_available().await.ok();
+
+    let package_info = app_handle.package_info();
+    let state = app_handle.state::<AppData>();
+    let model_manager = app_handle.state::<ModelManager>();
+    let (input_devices, input_device_error) = match list_input_devices(&state) {
+        Ok(devices) => (devices, None),
+        Err(e) => (Vec::new(), Some(e)),
+    };
+
+    let report = DiagnosticReport {
+        app_name: package_info.name.clone(),
+        app_version: package_info.version.to_string(),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        ffmpeg,
+        input_devices,
+        input_device_error,
+        loaded_models: model_manager.loaded_models(),
+        sessions: session_diagnostics(&state),
+    };
+
+    println!(
+        "[Diagnostics] Report generated: {} devices, {} models, {} sessions",
+        report.input_devices.len(),
+        report.loaded_models.len(),
+        report.sessions.len()
+    );
+    serde_json::to_string_pretty(&report).map_err(|e| format!("Failed to serialize report: {}", e))
+}
@@ -6,48 +6,233 @@ pub struct SignalResult {
     message: String,
 }
 
+/// Outcome of waiting for a process to exit
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WaitResult {
+    exited: bool,
+    /// `None` if the process was killed by a signal, or if it isn't a child of
+    /// this app so its exit code can't be collected
+    exit_code: Option<i32>,
+}
+
+/// Send `signal` to a process by PID (Unix only).
+#[cfg(unix)]
+fn send_signal_unix(pid: u32, signal: nix::sys::signal::Signal) -> SignalResult {
+    use nix::sys::signal::kill;
+    use nix::unistd::Pid;
+
+    let process_pid = Pid::from_raw(pid as i32);
+
+    match kill(process_pid, signal) {
+        Ok(_) => SignalResult {
+            success: true,
+            message: format!("{} sent to process {}", signal, pid),
+        },
+        Err(err) => SignalResult {
+            success: false,
+            message: format!("Failed to send {} to process {}: {}", signal, pid, err),
+        },
+    }
+}
+
+/// Send a console control event to a process group by PID (Windows only).
+#[cfg(windows)]
+fn send_console_ctrl_event(pid: u32, event: u32, name: &str) -> SignalResult {
+    use windows_sys::Win32::System::Console::GenerateConsoleCtrlEvent;
+
+    let result = unsafe { GenerateConsoleCtrlEvent(event, pid) };
+
+    if result != 0 {
+        SignalResult {
+            success: true,
+            message: format!("{} event sent to process {}", name, pid),
+        }
+    } else {
+        SignalResult {
+            success: false,
+            message: format!("Failed to send {} to process {}", name, pid),
+        }
+    }
+}
+
 /// Send a SIGINT signal to a process by PID.
 /// This is equivalent to Ctrl+C and allows graceful shutdown.
 #[tauri::command]
 pub fn send_sigint(pid: u32) -> SignalResult {
     #[cfg(unix)]
     {
-        use nix::sys::signal::{kill, Signal};
-        use nix::unistd::Pid;
-        
-        let process_pid = Pid::from_raw(pid as i32);
-        
-        match kill(process_pid, Signal::SIGINT) {
-            Ok(_) => SignalResult {
-                success: true,
-                message: format!("SIGINT sent to process {}", pid),
-            },
-            Err(err) => SignalResult {
-                success: false,
-                message: format!("Failed to send SIGINT to process {}: {}", pid, err),
-            },
-        }
+        send_signal_unix(pid, nix::sys::signal::Signal::SIGINT)
     }
-    
+
     #[cfg(windows)]
     {
         // Windows: Use Ctrl+C event for console processes
-        use windows_sys::Win32::System::Console::{GenerateConsoleCtrlEvent, CTRL_C_EVENT};
-        
+        use windows_sys::Win32::System::Console::CTRL_C_EVENT;
+        send_console_ctrl_event(pid, CTRL_C_EVENT, "Ctrl+C")
+    }
+}
+
+/// Send a SIGTERM signal to a process by PID.
+/// Some processes flush buffers and clean up on SIGTERM but ignore SIGINT.
+///
+/// Windows has no SIGTERM; the closest analogue is a Ctrl+Break event, which
+/// only reaches console processes started in their own process group
+/// (`pid` is treated as the group ID) and which many programs handle like
+/// Ctrl+C.
+#[tauri::command]
+pub fn send_sigterm(pid: u32) -> SignalResult {
+    #[cfg(unix)]
+    {
+        send_signal_unix(pid, nix::sys::signal::Signal::SIGTERM)
+    }
+
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::System::Console::CTRL_BREAK_EVENT;
+        send_console_ctrl_event(pid, CTRL_BREAK_EVENT, "Ctrl+Break")
+    }
+}
+
+/// Forcefully terminate a process by PID.
+/// Sends SIGKILL on Unix and calls TerminateProcess on Windows, so the process
+/// gets no chance to clean up; try `send_sigint` first.
+#[tauri::command]
+pub fn kill_process(pid: u32) -> SignalResult {
+    #[cfg(unix)]
+    {
+        send_signal_unix(pid, nix::sys::signal::Signal::SIGKILL)
+    }
+
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::Foundation::CloseHandle;
+        use windows_sys::Win32::System::Threading::{
+            OpenProcess, TerminateProcess, PROCESS_TERMINATE,
+        };
+
         unsafe {
-            let result = GenerateConsoleCtrlEvent(CTRL_C_EVENT, pid);
-            
+            let handle = OpenProcess(PROCESS_TERMINATE, 0, pid);
+            if handle.is_null() {
+                return SignalResult {
+                    success: false,
+                    message: format!("Failed to open process {}", pid),
+                };
+            }
+
+            let result = TerminateProcess(handle, 1);
+            CloseHandle(handle);
+
             if result != 0 {
                 SignalResult {
                     success: true,
-                    message: format!("Ctrl+C event sent to process {}", pid),
+                    message: format!("Process {} terminated", pid),
                 }
             } else {
                 SignalResult {
                     success: false,
-                    message: format!("Failed to send Ctrl+C to process {}", pid),
+                    message: format!("Failed to terminate process {}", pid),
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Wait up to `timeout_ms` for a process to exit.
+/// Together with `send_sigint` and `kill_process` this gives a graceful-then-
+/// forceful shutdown: interrupt, wait, and kill only if it's still running.
+#[tauri::command]
+pub async fn wait_for_process(pid: u32, timeout_ms: u64) -> WaitResult {
+    // The wait blocks for up to the whole timeout, so keep it off the async runtime
+    tokio::task::spawn_blocking(move || wait_for_process_blocking(pid, timeout_ms))
+        .await
+        .unwrap_or(WaitResult {
+            exited: false,
+            exit_code: None,
+        })
+}
+
+/// Block the current thread until the process exits or `timeout_ms` passes
+fn wait_for_process_blocking(pid: u32, timeout_ms: u64) -> WaitResult {
+    #[cfg(unix)]
+    {
+        use nix::errno::Errno;
+        use nix::sys::signal::kill;
+        use nix::sys::wait::{waitpid, WaitPidFlag, WaitStatus};
+        use nix::unistd::Pid;
+        use std::time::{Duration, Instant};
+
+        let process_pid = Pid::from_raw(pid as i32);
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms);
+
+        // waitpid has no timeout, so poll it without blocking
+        loop {
+            match waitpid(process_pid, Some(WaitPidFlag::WNOHANG)) {
+                Ok(WaitStatus::Exited(_, code)) => {
+                    return WaitResult {
+                        exited: true,
+                        exit_code: Some(code),
+                    }
+                }
+                Ok(WaitStatus::Signaled(..)) => {
+                    return WaitResult {
+                        exited: true,
+                        exit_code: None,
+                    }
+                }
+                Ok(_) => {}
+                // Not our child: all we can tell is whether it still exists
+                Err(Errno::ECHILD) => {
+                    if kill(process_pid, None) == Err(Errno::ESRCH) {
+                        return WaitResult {
+                            exited: true,
+                            exit_code: None,
+                        };
+                    }
+                }
+                Err(_) => {}
+            }
+
+            if Instant::now() >= deadline {
+                return WaitResult {
+                    exited: false,
+                    exit_code: None,
+                };
+            }
+            std::thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    #[cfg(windows)]
+    {
+        use windows_sys::Win32::Foundation::{CloseHandle, WAIT_OBJECT_0};
+        use windows_sys::Win32::System::Threading::{
+            GetExitCodeProcess, OpenProcess, WaitForSingleObject, PROCESS_QUERY_LIMITED_INFORMATION,
+            PROCESS_SYNCHRONIZE,
+        };
+
+        unsafe {
+            let access = PROCESS_SYNCHRONIZE | PROCESS_QUERY_LIMITED_INFORMATION;
+            let handle = OpenProcess(access, 0, pid);
+            if handle.is_null() {
+                // The process is gone (or was never there)
+                return WaitResult {
+                    exited: true,
+                    exit_code: None,
+                };
+            }
+
+            let timeout = u32::try_from(timeout_ms).unwrap_or(u32::MAX - 1);
+            let exited = WaitForSingleObject(handle, timeout) == WAIT_OBJECT_0;
+            let mut code = 0u32;
+            let exit_code = if exited && GetExitCodeProcess(handle, &mut code) != 0 {
+                Some(code as i32)
+            } else {
+                None
+            };
+            CloseHandle(handle);
+
+            WaitResult { exited, exit_code }
+        }
+    }
+}
@@ -4,21 +4,61 @@ use tauri_plugin_aptabase::EventTracker;
 
 pub mod recorder;
 use recorder::commands::{
-    cancel_recording, close_recording_session, enumerate_recording_devices,
-    get_current_recording_id, init_recording_session, start_recording, stop_recording, AppData,
+    cancel_recording, check_disk_space, clear_noise_gate, close_recording_session, disable_agc,
+    enable_agc, enumerate_hosts, enumerate_loopback_devices, enumerate_recording_devices,
+    enumerate_recording_devices_with_caps, estimate_storage, get_current_recording_id,
+    get_recording_device_error, init_recording_session, list_recording_sessions,
+    normalize_recording, pause_recording, preview_recording_config, push_to_talk_end,
+    push_to_talk_start, recover_recording, resume_recording, set_device_poll_interval_ms,
+    set_noise_gate, start_level_monitor, start_recording, stop_level_monitor, stop_recording,
+    AppData,
 };
 
 pub mod transcription;
-use transcription::{transcribe_audio_whisper, transcribe_audio_parakeet, ModelManager};
+use transcription::{
+    cancel_conversion, convert_with_cancellation, evict_model, export_raw_pcm,
+    get_inference_threads, get_loaded_model_info, get_model_cache_capacity, get_model_cache_state,
+    get_model_idle_timeout_secs, get_use_gpu, is_model_loaded, set_inference_threads,
+    set_model_cache_capacity, set_model_idle_timeout_secs, set_use_gpu, switch_model,
+    trigger_idle_check, unload_model, warmup_model,
+    transcribe_audio_whisper, transcribe_audio_whisper_full, transcribe_audio_whisper_streaming,
+    transcribe_audio_whisper_json, transcribe_audio_whisper_srt, transcribe_audio_whisper_vtt,
+    transcribe_audio_whisper_with_timestamps, transcribe_audio_parakeet, CancellationTokens,
+    ModelManager,
+};
+use transcription::batch::{convert_folder, transcribe_directory};
+use transcription::capabilities::get_capabilities;
+use transcription::export::export_chapters;
+use transcription::ffmpeg::{check_ffmpeg_available, set_ffmpeg_path};
+use transcription::gpu::get_gpu_memory;
+use transcription::long_audio::transcribe_long_audio;
+use transcription::memory::can_load_model;
+use transcription::precheck::precheck_audio;
+use transcription::translate::transcribe_with_translation;
+use transcription::url::transcribe_url;
+use transcription::verify::verify_transcript;
 
 pub mod windows_path;
 use windows_path::fix_windows_path;
 
 pub mod graceful_shutdown;
-use graceful_shutdown::send_sigint;
+use graceful_shutdown::{kill_process, send_sigint, send_sigterm, wait_for_process};
 
 pub mod command;
-use command::{execute_command, spawn_command};
+use command::{execute_command, execute_command_streaming, spawn_command};
+
+pub mod audio_ops;
+use audio_ops::{
+    compute_spectrogram, compute_waveform, concatenate_wav_files, detect_silence_regions,
+    map_trimmed_time, remove_silences, split_wav_at_timestamps, time_stretch_wav, trim_silence,
+    trim_wav_file,
+};
+
+pub mod audio_info;
+use audio_info::{get_audio_duration, get_audio_info};
+
+pub mod diagnostics;
+use diagnostics::generate_diagnostic_report;
 
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -59,7 +99,13 @@ pub async fn run() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_opener::init())
         .manage(AppData::new())
-        .manage(ModelManager::new());
+        .manage(ModelManager::new())
+        .manage(CancellationTokens::new())
+        .setup(|app| {
+            // Let the frontend know when microphones are plugged in or removed
+            app.state::<AppData>().start_device_watcher(app.handle().clone())?;
+            Ok(())
+        });
 
     #[cfg(desktop)]
     {
@@ -76,18 +122,96 @@ pub async fn run() {
         write_text,
         // Audio recorder commands
         get_current_recording_id,
+        get_recording_device_error,
         enumerate_recording_devices,
+        enumerate_recording_devices_with_caps,
+        enumerate_hosts,
+        enumerate_loopback_devices,
         init_recording_session,
+        preview_recording_config,
         close_recording_session,
         start_recording,
+        pause_recording,
+        recover_recording,
+        normalize_recording,
+        resume_recording,
+        push_to_talk_start,
+        push_to_talk_end,
         stop_recording,
         cancel_recording,
+        list_recording_sessions,
+        estimate_storage,
+        check_disk_space,
+        start_level_monitor,
+        stop_level_monitor,
+        set_device_poll_interval_ms,
+        set_noise_gate,
+        clear_noise_gate,
+        enable_agc,
+        disable_agc,
         transcribe_audio_whisper,
+        transcribe_audio_whisper_full,
+        transcribe_audio_whisper_streaming,
+        transcribe_audio_whisper_srt,
+        transcribe_audio_whisper_vtt,
+        transcribe_audio_whisper_json,
+        transcribe_audio_whisper_with_timestamps,
         transcribe_audio_parakeet,
+        verify_transcript,
+        get_capabilities,
+        check_ffmpeg_available,
+        set_ffmpeg_path,
+        transcribe_with_translation,
+        transcribe_long_audio,
+        transcribe_url,
+        switch_model,
+        warmup_model,
+        set_inference_threads,
+        get_inference_threads,
+        get_model_cache_state,
+        get_loaded_model_info,
+        set_model_cache_capacity,
+        get_model_cache_capacity,
+        evict_model,
+        unload_model,
+        is_model_loaded,
+        set_model_idle_timeout_secs,
+        get_model_idle_timeout_secs,
+        set_use_gpu,
+        get_use_gpu,
+        trigger_idle_check,
+        convert_with_cancellation,
+        cancel_conversion,
+        export_raw_pcm,
+        convert_folder,
+        transcribe_directory,
+        export_chapters,
+        get_gpu_memory,
+        can_load_model,
+        precheck_audio,
         send_sigint,
+        send_sigterm,
+        kill_process,
+        wait_for_process,
         // Command execution (prevents console window flash on Windows)
         execute_command,
+        execute_command_streaming,
         spawn_command,
+        // Audio file operations
+        remove_silences,
+        map_trimmed_time,
+        compute_waveform,
+        compute_spectrogram,
+        concatenate_wav_files,
+        trim_wav_file,
+        split_wav_at_timestamps,
+        trim_silence,
+        detect_silence_regions,
+        time_stretch_wav,
+        get_audio_duration,
+        get_audio_info,
+        // Bug report diagnostics
+        generate_diagnostic_report,
     ]);
 
     let app = builder
@@ -95,6 +219,10 @@ pub async fn run() {
         .expect("error while building tauri application");
 
     app.run(|handler, event| {
+        if matches!(event, tauri::RunEvent::Exit { .. }) {
+            handler.state::<AppData>().stop_device_watcher();
+        }
+
         // Only track events if Aptabase is enabled (key is not empty)
         if !aptabase_key.is_empty() {
             match event {
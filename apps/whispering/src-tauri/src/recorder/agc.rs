@@ -0,0 +1,83 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Length of the trailing window the level is measured over
+const WINDOW_MS: f32 = 200.0;
+
+/// Share of the way the gain moves toward its target every millisecond
+const DEFAULT_SMOOTHING_COEFF: f32 = 0.01;
+
+/// Below this RMS (about -60 dBFS) the input counts as silence and the gain is
+/// held, rather than raised to its maximum on background hiss
+const SILENCE_RMS: f32 = 0.001;
+
+/// Automatic gain control applied to captured audio before it is written,
+/// shared with the stream callback; `None` when AGC is off
+pub type SharedAgc = Arc<Mutex<Option<AgcState>>>;
+
+pub fn new_shared_agc() -> SharedAgc {
+    Arc::new(Mutex::new(None))
+}
+
+/// Raises (or lowers) quiet input toward a target level while recording
+///
+/// The gain is the ratio of `target_rms` to the RMS of the last 200 ms of
+/// input, capped at `max_gain`, and eased toward over time so the level
+/// doesn't pump. Output is clamped to full scale.
+#[derive(Debug, Clone)]
+pub struct AgcState {
+    /// Linear RMS the input is brought to
+    pub target_rms: f32,
+    /// Largest linear gain applied
+    pub max_gain: f32,
+    /// Share of the way the gain moves toward its target every millisecond
+    pub smoothing_coeff: f32,
+    gain: f32,
+    /// Squares of the trailing window's samples, and their sum
+    window: VecDeque<f32>,
+    window_sum: f64,
+}
+
+impl AgcState {
+    pub fn new(target_rms_dbfs: f32, max_gain_db: f32) -> Self {
+        Self {
+            target_rms: 10f32.powf(target_rms_dbfs / 20.0),
+            max_gain: 10f32.powf(max_gain_db.max(0.0) / 20.0),
+            smoothing_coeff: DEFAULT_SMOOTHING_COEFF,
+            gain: 1.0,
+            window: VecDeque::new(),
+            window_sum: 0.0,
+        }
+    }
+
+    /// Apply the gain to interleaved samples in place
+    pub fn process(&mut self, samples: &mut [f32], sample_rate: u32, channels: u16) {
+        let channels = channels.max(1) as usize;
+        let frames_per_ms = sample_rate as f32 / 1000.0;
+        let window_len = ((WINDOW_MS * frames_per_ms) as usize).max(1) * channels;
+        // Spread the per-millisecond coefficient over the frames in a millisecond
+        let smoothing_coeff = self.smoothing_coeff.clamp(0.0, 1.0);
+        let frame_coeff = 1.0 - (1.0 - smoothing_coeff).powf(1.0 / frames_per_ms);
+
+        for frame in samples.chunks_mut(channels) {
+            for &sample in frame.iter() {
+                let square = sample * sample;
+                self.window.push_back(square);
+                self.window_sum += square as f64;
+            }
+            while self.window.len() > window_len {
+                self.window_sum -= self.window.pop_front().unwrap_or(0.0) as f64;
+            }
+
+            let rms = (self.window_sum.max(0.0) / self.window.len() as f64).sqrt() as f32;
+            if rms > SILENCE_RMS {
+                let target_gain = (self.target_rms / rms).min(self.max_gain);
+                self.gain += (target_gain - self.gain) * frame_coeff;
+            }
+
+            for sample in frame {
+                *sample = (*sample * self.gain).clamp(-1.0, 1.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,121 @@
+use crate::audio_ops::rms_db;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tracing::debug;
+
+/// Number of most recent samples the level is computed over
+const LEVEL_WINDOW_SAMPLES: usize = 2048;
+
+/// How often a level is reported
+const LEVEL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Sample magnitude treated as clipping
+const CLIP_THRESHOLD: f32 = 0.999;
+
+/// Input level in dBFS - emitted to frontend
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioLevel {
+    pub rms_dbfs: f32,
+    pub peak_dbfs: f32,
+    pub clipping: bool,
+}
+
+/// Called from the level thread every `LEVEL_INTERVAL`
+pub type AudioLevelCallback = Box<dyn Fn(AudioLevel) + Send>;
+
+/// Rolling window of the latest samples, shared with the stream callback
+pub type LevelWindow = Arc<Mutex<VecDeque<f32>>>;
+
+pub fn new_level_window() -> LevelWindow {
+    Arc::new(Mutex::new(VecDeque::with_capacity(LEVEL_WINDOW_SAMPLES)))
+}
+
+/// Append samples from the stream callback, dropping the oldest
+///
+/// Skips the buffer if the level thread holds the lock rather than block the
+/// audio thread; a meter doesn't miss one buffer.
+pub fn push_samples(window: &LevelWindow, samples: impl Iterator<Item = f32>) {
+    let Ok(mut window) = window.try_lock() else {
+        return;
+    };
+    for sample in samples {
+        if window.len() == LEVEL_WINDOW_SAMPLES {
+            window.pop_front();
+        }
+        window.push_back(sample);
+    }
+}
+
+/// Level of a run of samples
+///
+/// Shared by the session VU meter and the standalone level monitor so both
+/// report the same units. An empty run reads as silence.
+pub fn measure(samples: impl IntoIterator<Item = f32>) -> AudioLevel {
+    let mut peak = 0.0f32;
+    let mut sum_squares = 0.0f64;
+    let mut count = 0usize;
+    for sample in samples {
+        peak = peak.max(sample.abs());
+        sum_squares += (sample as f64) * (sample as f64);
+        count += 1;
+    }
+    let mean_square = if count == 0 {
+        0.0
+    } else {
+        (sum_squares / count as f64) as f32
+    };
+
+    AudioLevel {
+        rms_dbfs: rms_db(mean_square),
+        // rms_db of a squared amplitude is its level in dBFS
+        peak_dbfs: rms_db(peak * peak),
+        clipping: peak >= CLIP_THRESHOLD,
+    }
+}
+
+/// Background thread reporting the level of a session's stream
+pub struct AudioLevelTask {
+    stop_tx: Option<mpsc::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl AudioLevelTask {
+    pub fn start(window: LevelWindow, on_level: AudioLevelCallback) -> Self {
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let handle = thread::spawn(move || {
+            // Runs until stopped or the task is dropped
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stop_rx.recv_timeout(LEVEL_INTERVAL) {
+                let level = match window.lock() {
+                    Ok(window) => measure(window.iter().copied()),
+                    Err(_) => break,
+                };
+                on_level(level);
+            }
+            debug!("Audio level task stopped");
+        });
+
+        Self {
+            stop_tx: Some(stop_tx),
+            handle: Some(handle),
+        }
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for AudioLevelTask {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
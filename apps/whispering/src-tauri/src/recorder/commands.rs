@@ -1,114 +1,731 @@
-use crate::recorder::recorder::{AudioRecording, RecorderState, Result};
-use std::path::PathBuf;
-use std::sync::Mutex;
-use tauri::State;
+use crate::recorder::agc::AgcState;
+use crate::recorder::audio_level::{AudioLevel, AudioLevelCallback};
+use crate::recorder::device_watcher::DeviceWatcher;
+use crate::recorder::level_monitor::LevelMonitor;
+use crate::recorder::noise_gate::NoiseGate;
+use crate::recorder::loopback;
+use crate::recorder::rate_monitor::{DriftCallback, SampleRateDrift};
+use crate::recorder::recorder::{
+    available_host_names, enumerate_devices_with_caps, preview_config, AudioRecording,
+    DeviceCapabilities, DeviceErrorCallback, DeviceSwitchCallback, RecorderState, RecordingConfig,
+    RecordingMode, Result, SessionCallbacks, SessionOptions,
+};
+use serde::{Deserialize, Serialize};
+use crate::recorder::storage::{estimate_storage_bytes, StorageEstimate, StorageFormat};
+use crate::recorder::wav_writer::{
+    normalize_wav_file, BextMetadata, PcmFormat, WavWriter, WavWriterConfig,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, State};
 use tracing::{debug, info};
 
-/// Application state containing the recorder
+/// Session used when a command doesn't specify one (single-recorder behaviour)
+pub const DEFAULT_SESSION_ID: &str = "default";
+
+/// Event emitted when a device's effective sample rate changes mid-recording
+pub const SAMPLE_RATE_CHANGED_EVENT: &str = "sample-rate-changed";
+
+/// Payload of the `sample-rate-changed` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SampleRateChanged {
+    session_id: String,
+    #[serde(flatten)]
+    drift: SampleRateDrift,
+}
+
+/// Event emitted every ~50 ms with the live input level of an open session
+pub const AUDIO_LEVEL_EVENT: &str = "get_audio_level";
+
+/// Payload of the `get_audio_level` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionAudioLevel {
+    session_id: String,
+    #[serde(flatten)]
+    level: AudioLevel,
+}
+
+/// Event emitted when a session following the default device moves to a new one
+pub const DEVICE_SWITCHED_EVENT: &str = "device-switched";
+
+/// Payload of the `device-switched` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DeviceSwitched {
+    session_id: String,
+    device_name: String,
+}
+
+/// Event emitted when a session's stream fails, e.g. because the device was unplugged
+pub const RECORDING_DEVICE_ERROR_EVENT: &str = "recording-device-error";
+
+/// Payload of the `recording-device-error` event
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordingDeviceError {
+    session_id: String,
+    message: String,
+}
+
+/// Application state containing one recorder per recording session
+///
+/// Each session owns its own stream and WAV writer, so several devices can be
+/// recorded at the same time. The default session always exists.
 pub struct AppData {
-    pub recorder: Mutex<RecorderState>,
+    sessions: Mutex<HashMap<String, Arc<Mutex<RecorderState>>>>,
+    level_monitor: Mutex<LevelMonitor>,
+    device_watcher: Mutex<DeviceWatcher>,
 }
 
 impl AppData {
     pub fn new() -> Self {
+        let mut sessions = HashMap::new();
+        sessions.insert(
+            DEFAULT_SESSION_ID.to_string(),
+            Arc::new(Mutex::new(RecorderState::new())),
+        );
         Self {
-            recorder: Mutex::new(RecorderState::new()),
+            sessions: Mutex::new(sessions),
+            level_monitor: Mutex::new(LevelMonitor::new()),
+            device_watcher: Mutex::new(DeviceWatcher::new()),
+        }
+    }
+
+    /// Start watching for input devices being plugged in or removed
+    ///
+    /// Called once the app is set up; emits `audio-devices-changed`.
+    pub fn start_device_watcher(&self, app_handle: tauri::AppHandle) -> Result<()> {
+        let mut watcher = self
+            .device_watcher
+            .lock()
+            .map_err(|e| format!("Failed to lock device watcher: {}", e))?;
+        watcher.start(app_handle);
+        Ok(())
+    }
+
+    /// Stop the device watcher, e.g. when the app exits
+    pub fn stop_device_watcher(&self) {
+        if let Ok(mut watcher) = self.device_watcher.lock() {
+            watcher.stop();
         }
     }
+
+    /// Get the recorder for a session (the default session when `None`)
+    pub fn session(&self, session_id: Option<&str>) -> Result<Arc<Mutex<RecorderState>>> {
+        let session_id = session_id.unwrap_or(DEFAULT_SESSION_ID);
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|e| format!("Failed to lock recording sessions: {}", e))?;
+        sessions
+            .get(session_id)
+            .cloned()
+            .ok_or_else(|| format!("Recording session '{}' not found", session_id))
+    }
+
+    /// Get the recorder for a session, creating it if it doesn't exist yet
+    fn session_or_create(&self, session_id: &str) -> Result<Arc<Mutex<RecorderState>>> {
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|e| format!("Failed to lock recording sessions: {}", e))?;
+        Ok(sessions
+            .entry(session_id.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(RecorderState::new())))
+            .clone())
+    }
+
+    /// Forget a session; the default session is kept around for reuse
+    fn remove_session(&self, session_id: &str) -> Result<()> {
+        if session_id == DEFAULT_SESSION_ID {
+            return Ok(());
+        }
+        let mut sessions = self
+            .sessions
+            .lock()
+            .map_err(|e| format!("Failed to lock recording sessions: {}", e))?;
+        sessions.remove(session_id);
+        Ok(())
+    }
+
+    /// IDs of all known sessions
+    pub fn session_ids(&self) -> Result<Vec<String>> {
+        let sessions = self
+            .sessions
+            .lock()
+            .map_err(|e| format!("Failed to lock recording sessions: {}", e))?;
+        Ok(sessions.keys().cloned().collect())
+    }
 }
 
 #[tauri::command]
-pub async fn enumerate_recording_devices(state: State<'_, AppData>) -> Result<Vec<String>> {
-    debug!("Enumerating recording devices");
-    let recorder = state
-        .recorder
+pub async fn enumerate_recording_devices(
+    host_name: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<Vec<String>> {
+    debug!("Enumerating recording devices (host: {:?})", host_name);
+    let session = state.session(None)?;
+    let recorder = session
         .lock()
         .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.enumerate_devices()
+    recorder.enumerate_devices(host_name.as_deref())
 }
 
+/// List recording devices with the sample rates, channel counts and sample
+/// formats each supports
+///
+/// Lets the UI offer only rates a microphone can actually record at before a
+/// session is configured.
 #[tauri::command]
-pub async fn init_recording_session(
+pub async fn enumerate_recording_devices_with_caps(
+    host_name: Option<String>,
+) -> Result<Vec<DeviceCapabilities>> {
+    debug!("Enumerating recording device capabilities (host: {:?})", host_name);
+    enumerate_devices_with_caps(host_name.as_deref())
+}
+
+/// List the sources system audio (e.g. a meeting app) can be recorded from
+///
+/// Pass one as `device_identifier` with `loopback` to `init_recording_session`.
+/// Only available where a loopback source exists: output devices on Windows,
+/// virtual devices such as BlackHole on macOS and PulseAudio/PipeWire monitor
+/// sources on Linux. Other platforms return an error.
+#[tauri::command]
+pub async fn enumerate_loopback_devices(host_name: Option<String>) -> Result<Vec<String>> {
+    debug!("Enumerating loopback devices (host: {:?})", host_name);
+    loopback::enumerate_loopback_devices(host_name.as_deref())
+}
+
+/// List the audio host APIs (backends) that can be passed as `host_name`
+#[tauri::command]
+pub async fn enumerate_hosts() -> Result<Vec<String>> {
+    debug!("Enumerating audio hosts");
+    Ok(available_host_names())
+}
+
+/// Config a recording on this device would use, without opening a stream
+///
+/// Lets the UI show e.g. "will record at 44100 Hz stereo" before recording,
+/// since the negotiation falls back to other rates and channel counts when the
+/// device doesn't support the requested ones.
+#[tauri::command]
+pub async fn preview_recording_config(
     device_identifier: String,
-    recording_id: String,
-    output_folder: String,
     sample_rate: Option<u32>,
+    host_name: Option<String>,
+) -> Result<RecordingConfig> {
+    debug!("Previewing recording config: device={}", device_identifier);
+    preview_config(&device_identifier, sample_rate, host_name.as_deref())
+}
+
+/// Options for `init_recording_session`, sent by the frontend as one object
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingSessionOptions {
+    /// Input device name or "default"; with `loopback`, a system audio source
+    /// from `enumerate_loopback_devices` (or "default")
+    pub device_identifier: String,
+    pub recording_id: String,
+    pub output_folder: String,
+    /// Preferred sample rate; the closest supported one is used otherwise
+    pub sample_rate: Option<u32>,
+    /// Description written to a Broadcast Wave `bext` chunk
+    pub bext_description: Option<String>,
+    /// Also refresh the WAV header after this much audio data, bounding how
+    /// much of the file a crash can leave unreadable
+    pub header_update_bytes: Option<u64>,
+    /// Filter out a microphone's constant DC offset before writing
+    pub remove_dc_offset: Option<bool>,
+    /// 16- or 24-bit integer samples instead of 32-bit float
+    pub pcm_format: Option<PcmFormat>,
+    /// How long audio sits in memory before reaching the OS (default 1000)
+    pub flush_interval_ms: Option<u64>,
+    /// Also fsync each flush so a power cut can't lose it
+    pub sync_to_disk: Option<bool>,
+    /// Audio backend (see `enumerate_hosts`) instead of CPAL's default
+    pub host_name: Option<String>,
+    /// Move to the new system default device if it changes before recording
+    pub follow_default_device: Option<bool>,
+    /// Capture system audio instead of an input device
+    pub loopback: Option<bool>,
+    pub mode: Option<RecordingMode>,
+}
+
+/// Initialize a recording session, returning its session ID
+///
+/// Omitting `session_id` uses the default session, which matches the original
+/// single-recorder behaviour. Passing distinct IDs allows recording from
+/// several devices simultaneously. Everything else comes in `options` (see
+/// `RecordingSessionOptions`).
+/// With `follow_default_device` and the "default" device, the session moves to
+/// the new system default device (e.g. a headset after docking) if it changes
+/// before recording starts, emitting `device-switched`. While the session is
+/// open, its input level is emitted as `get_audio_level` events for a VU meter,
+/// and a stream failure such as the device being unplugged is emitted as
+/// `recording-device-error`. With `loopback`, an error is returned if no such
+/// system audio source exists.
+#[tauri::command]
+pub async fn init_recording_session(
+    options: RecordingSessionOptions,
+    session_id: Option<String>,
     state: State<'_, AppData>,
-    _app_handle: tauri::AppHandle,
-) -> Result<()> {
+    app_handle: tauri::AppHandle,
+) -> Result<String> {
+    let RecordingSessionOptions {
+        device_identifier,
+        recording_id,
+        output_folder,
+        sample_rate,
+        bext_description,
+        header_update_bytes,
+        remove_dc_offset,
+        pcm_format,
+        flush_interval_ms,
+        sync_to_disk,
+        host_name,
+        follow_default_device,
+        loopback,
+        mode,
+    } = options;
+
+    let session_id = session_id.unwrap_or_else(|| DEFAULT_SESSION_ID.to_string());
     info!(
-        "Initializing recording session: device={}, id={}, folder={}, sample_rate={:?}",
-        device_identifier, recording_id, output_folder, sample_rate
+        "Initializing recording session {}: device={}, host={:?}, id={}, folder={}, sample_rate={:?}, bext={}",
+        session_id,
+        device_identifier,
+        host_name,
+        recording_id,
+        output_folder,
+        sample_rate,
+        bext_description.is_some()
     );
 
     // Use the provided output folder
     let recordings_dir = PathBuf::from(output_folder);
-    
+
     // Create the directory if it doesn't exist
     if !recordings_dir.exists() {
         std::fs::create_dir_all(&recordings_dir)
             .map_err(|e| format!("Failed to create output folder: {}", e))?;
     }
-    
+
     // Validate it's a directory (not a file)
     if !recordings_dir.is_dir() {
         return Err(format!("Output path is not a directory: {:?}", recordings_dir));
     }
 
+    // Broadcast Wave metadata is opt-in
+    let bext = bext_description.map(BextMetadata::new);
+
+    // Warn the frontend if the device changes rate mid-stream (e.g. Bluetooth profile switch)
+    let drift_session_id = session_id.clone();
+    let drift_app_handle = app_handle.clone();
+    let on_rate_drift: DriftCallback = Arc::new(move |drift| {
+        let _ = drift_app_handle.emit(
+            SAMPLE_RATE_CHANGED_EVENT,
+            SampleRateChanged {
+                session_id: drift_session_id.clone(),
+                drift,
+            },
+        );
+    });
+
+    let switch_session_id = session_id.clone();
+    let switch_app_handle = app_handle.clone();
+    let on_device_switched: DeviceSwitchCallback = Box::new(move |device_name| {
+        let _ = switch_app_handle.emit(
+            DEVICE_SWITCHED_EVENT,
+            DeviceSwitched {
+                session_id: switch_session_id.clone(),
+                device_name,
+            },
+        );
+    });
+
+    let error_session_id = session_id.clone();
+    let error_app_handle = app_handle.clone();
+    let on_device_error: DeviceErrorCallback = Arc::new(move |message| {
+        let _ = error_app_handle.emit(
+            RECORDING_DEVICE_ERROR_EVENT,
+            RecordingDeviceError {
+                session_id: error_session_id.clone(),
+                message,
+            },
+        );
+    });
+
+    // Live VU meter for the UI
+    let level_session_id = session_id.clone();
+    let on_audio_level: AudioLevelCallback = Box::new(move |level| {
+        let _ = app_handle.emit(
+            AUDIO_LEVEL_EVENT,
+            SessionAudioLevel {
+                session_id: level_session_id.clone(),
+                level,
+            },
+        );
+    });
+
     // Initialize the session with optional sample rate
-    let mut recorder = state
-        .recorder
+    let session = state.session_or_create(&session_id)?;
+    let mut recorder = session
         .lock()
         .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.init_session(device_identifier, recordings_dir, recording_id, sample_rate)
+    recorder.init_session(
+        SessionOptions {
+            device_name: device_identifier,
+            output_folder: recordings_dir,
+            recording_id,
+            preferred_sample_rate: sample_rate,
+            writer_config: WavWriterConfig {
+                format: pcm_format.unwrap_or_default(),
+                bext,
+                header_update_bytes,
+                remove_dc_offset: remove_dc_offset.unwrap_or(false),
+                flush_interval_ms,
+                sync_to_disk: sync_to_disk.unwrap_or(false),
+                rf64_threshold_bytes: None,
+            },
+            host_name,
+            follow_default_device: follow_default_device.unwrap_or(false),
+            loopback: loopback.unwrap_or(false),
+            mode: mode.unwrap_or_default(),
+        },
+        SessionCallbacks {
+            on_rate_drift: Some(on_rate_drift),
+            on_device_switched: Some(on_device_switched),
+            on_audio_level: Some(on_audio_level),
+            on_device_error: Some(on_device_error),
+        },
+    )?;
+
+    Ok(session_id)
 }
 
 #[tauri::command]
-pub async fn start_recording(state: State<'_, AppData>) -> Result<()> {
+pub async fn start_recording(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
     info!("Starting recording");
-    let mut recorder = state
-        .recorder
+    let session = state.session(session_id.as_deref())?;
+    let mut recorder = session
         .lock()
         .map_err(|e| format!("Failed to lock recorder: {}", e))?;
     recorder.start_recording()
 }
 
+/// Pause recording without finalizing the file; a no-op if already paused
+#[tauri::command]
+pub async fn pause_recording(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
+    info!("Pausing recording");
+    let session = state.session(session_id.as_deref())?;
+    let mut recorder = session
+        .lock()
+        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    recorder.pause_recording()
+}
+
+/// Resume a paused recording into the same file; a no-op if not paused
+#[tauri::command]
+pub async fn resume_recording(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
+    info!("Resuming recording");
+    let session = state.session(session_id.as_deref())?;
+    let mut recorder = session
+        .lock()
+        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    recorder.resume_recording()
+}
+
+/// Start capturing into a push-to-talk session's file (talk button pressed)
+#[tauri::command]
+pub async fn push_to_talk_start(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
+    let session = state.session(session_id.as_deref())?;
+    let recorder = session
+        .lock()
+        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    recorder.push_start()
+}
+
+/// Stop capturing into a push-to-talk session's file (talk button released)
 #[tauri::command]
-pub async fn stop_recording(state: State<'_, AppData>) -> Result<AudioRecording> {
+pub async fn push_to_talk_end(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
+    let session = state.session(session_id.as_deref())?;
+    let recorder = session
+        .lock()
+        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    recorder.push_end()
+}
+
+#[tauri::command]
+pub async fn stop_recording(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<AudioRecording> {
     info!("Stopping recording");
-    let mut recorder = state
-        .recorder
+    let session = state.session(session_id.as_deref())?;
+    let mut recorder = session
         .lock()
         .map_err(|e| format!("Failed to lock recorder: {}", e))?;
     recorder.stop_recording()
 }
 
+/// Silence input quieter than `threshold_dbfs` before it is written
+///
+/// Applied in real time while recording, so background noise between speech
+/// never reaches the file. The gate opens over `attack_ms` and closes over
+/// `release_ms` to avoid clicks. Replaces any gate already set and stays set
+/// for later recordings in the session.
+#[tauri::command]
+pub async fn set_noise_gate(
+    threshold_dbfs: f32,
+    attack_ms: f32,
+    release_ms: f32,
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
+    info!(
+        "Setting noise gate: {} dBFS, attack {} ms, release {} ms",
+        threshold_dbfs, attack_ms, release_ms
+    );
+    let session = state.session(session_id.as_deref())?;
+    let recorder = session
+        .lock()
+        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    recorder.set_noise_gate(Some(NoiseGate::new(threshold_dbfs, attack_ms, release_ms)))
+}
+
+/// Remove the session's noise gate so audio is written unchanged
+#[tauri::command]
+pub async fn clear_noise_gate(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
+    info!("Clearing noise gate");
+    let session = state.session(session_id.as_deref())?;
+    let recorder = session
+        .lock()
+        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    recorder.set_noise_gate(None)
+}
+
+/// Turn on automatic gain control, bringing input toward `target_rms_dbfs`
+///
+/// For a microphone far from the speaker, whose audio is too quiet to
+/// transcribe reliably. The gain follows the level of the last 200 ms and is
+/// never more than `max_gain_db`. Applied in real time after the noise gate,
+/// and stays on for later recordings in the session.
+#[tauri::command]
+pub async fn enable_agc(
+    target_rms_dbfs: f32,
+    max_gain_db: f32,
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
+    info!("Enabling AGC: target {} dBFS, max gain {} dB", target_rms_dbfs, max_gain_db);
+    let session = state.session(session_id.as_deref())?;
+    let recorder = session
+        .lock()
+        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    recorder.set_agc(Some(AgcState::new(target_rms_dbfs, max_gain_db)))
+}
+
+/// Turn off automatic gain control for the session
+#[tauri::command]
+pub async fn disable_agc(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
+    info!("Disabling AGC");
+    let session = state.session(session_id.as_deref())?;
+    let recorder = session
+        .lock()
+        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    recorder.set_agc(None)
+}
+
+/// Error the session's stream reported, e.g. because the device was unplugged
+///
+/// Also emitted as a `recording-device-error` event when it happens, and
+/// returned by `stop_recording` as `deviceError`.
+#[tauri::command]
+pub async fn get_recording_device_error(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<Option<String>> {
+    let session = state.session(session_id.as_deref())?;
+    let recorder = session
+        .lock()
+        .map_err(|e| format!("Failed to lock recorder: {}", e))?;
+    Ok(recorder.get_device_error())
+}
+
 #[tauri::command]
-pub async fn cancel_recording(state: State<'_, AppData>) -> Result<()> {
+pub async fn cancel_recording(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
     info!("Cancelling recording");
-    let mut recorder = state
-        .recorder
+    let session = state.session(session_id.as_deref())?;
+    let mut recorder = session
         .lock()
         .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.cancel_recording()
+    recorder.cancel_recording()?;
+    drop(recorder);
+    state.remove_session(session_id.as_deref().unwrap_or(DEFAULT_SESSION_ID))
 }
 
 #[tauri::command]
-pub async fn close_recording_session(state: State<'_, AppData>) -> Result<()> {
+pub async fn close_recording_session(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<()> {
     info!("Closing recording session");
-    let mut recorder = state
-        .recorder
+    let session = state.session(session_id.as_deref())?;
+    let mut recorder = session
         .lock()
         .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-    recorder.close_session()
+    recorder.close_session()?;
+    drop(recorder);
+    state.remove_session(session_id.as_deref().unwrap_or(DEFAULT_SESSION_ID))
 }
 
 #[tauri::command]
-pub async fn get_current_recording_id(state: State<'_, AppData>) -> Result<Option<String>> {
+pub async fn get_current_recording_id(
+    session_id: Option<String>,
+    state: State<'_, AppData>,
+) -> Result<Option<String>> {
     debug!("Getting current recording ID");
-    let recorder = state
-        .recorder
+    let session = state.session(session_id.as_deref())?;
+    let recorder = session
         .lock()
         .map_err(|e| format!("Failed to lock recorder: {}", e))?;
     Ok(recorder.get_current_recording_id())
 }
+
+/// List the IDs of all recording sessions
+#[tauri::command]
+pub async fn list_recording_sessions(state: State<'_, AppData>) -> Result<Vec<String>> {
+    debug!("Listing recording sessions");
+    state.session_ids()
+}
+
+/// Estimate how much disk space a recording of the given length will use
+#[tauri::command]
+pub async fn estimate_storage(
+    duration_seconds: f64,
+    format: StorageFormat,
+    sample_rate: u32,
+    channels: u16,
+) -> Result<StorageEstimate> {
+    if sample_rate == 0 || channels == 0 {
+        return Err("Sample rate and channels must be greater than zero".to_string());
+    }
+    Ok(estimate_storage_bytes(duration_seconds, format, sample_rate, channels))
+}
+
+/// Free space in bytes on the volume holding `path`, e.g. the recordings
+/// folder, so the UI can warn before space runs out
+#[tauri::command]
+pub async fn check_disk_space(path: String) -> Result<u64> {
+    fs2::available_space(&path)
+        .map_err(|e| format!("Failed to check disk space in {}: {}", path, e))
+}
+
+/// Repair a WAV file left behind when the app crashed mid-recording
+///
+/// The header sizes are rewritten to cover all the audio on disk, so the file
+/// plays back in full. Files with valid headers are returned unchanged.
+#[tauri::command]
+pub async fn recover_recording(file_path: String) -> Result<AudioRecording> {
+    let recovered = WavWriter::recover(PathBuf::from(&file_path))
+        .map_err(|e| format!("Failed to recover recording {}: {}", file_path, e))?;
+    info!(
+        "Recovered recording {}: {:.2}s (repaired: {})",
+        file_path, recovered.duration_seconds, recovered.repaired
+    );
+
+    Ok(AudioRecording {
+        audio_data: Vec::new(),
+        sample_rate: recovered.sample_rate,
+        channels: recovered.channels,
+        duration_seconds: recovered.duration_seconds,
+        file_path: Some(file_path),
+        first_sample_time_ms: None,
+        summary: None,
+        sample_rate_unreliable: false,
+        paused_seconds: 0.0,
+        device_error: None,
+    })
+}
+
+/// Scale a finished recording so its loudest sample reaches `target_peak`
+/// (default 0.95 of full scale), returning the gain applied
+///
+/// Runs on the file after recording rather than while writing, so nothing is
+/// held back during capture.
+#[tauri::command]
+pub async fn normalize_recording(file_path: String, target_peak: Option<f32>) -> Result<f32> {
+    normalize_wav_file(Path::new(&file_path), target_peak.unwrap_or(0.95))
+        .map_err(|e| format!("Failed to normalize recording {}: {}", file_path, e))
+}
+
+/// Start emitting `input-level` events for a device without recording
+///
+/// Starting a new monitor replaces the previous one.
+#[tauri::command]
+pub async fn start_level_monitor(
+    device_identifier: String,
+    host_name: Option<String>,
+    state: State<'_, AppData>,
+    app_handle: tauri::AppHandle,
+) -> Result<()> {
+    info!("Starting level monitor: device={}", device_identifier);
+    let mut monitor = state
+        .level_monitor
+        .lock()
+        .map_err(|e| format!("Failed to lock level monitor: {}", e))?;
+    monitor.start(device_identifier, host_name, app_handle)
+}
+
+/// Set how often the input device list is checked for changes, returning the
+/// interval applied (at least 100 ms; 2000 ms by default)
+#[tauri::command]
+pub async fn set_device_poll_interval_ms(
+    interval_ms: u64,
+    state: State<'_, AppData>,
+) -> Result<u64> {
+    let watcher = state
+        .device_watcher
+        .lock()
+        .map_err(|e| format!("Failed to lock device watcher: {}", e))?;
+    let interval_ms = watcher.set_poll_interval_ms(interval_ms);
+    info!("Device poll interval set to {} ms", interval_ms);
+    Ok(interval_ms)
+}
+
+#[tauri::command]
+pub async fn stop_level_monitor(state: State<'_, AppData>) -> Result<()> {
+    info!("Stopping level monitor");
+    let mut monitor = state
+        .level_monitor
+        .lock()
+        .map_err(|e| format!("Failed to lock level monitor: {}", e))?;
+    monitor.stop();
+    Ok(())
+}
@@ -0,0 +1,115 @@
+use cpal::traits::{DeviceTrait, HostTrait};
+use serde::Serialize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, info, warn};
+
+/// Event emitted when input devices are plugged in or removed
+pub const AUDIO_DEVICES_CHANGED_EVENT: &str = "audio-devices-changed";
+
+/// How often the device list is checked unless configured otherwise
+const DEFAULT_POLL_INTERVAL_MS: u64 = 2000;
+
+/// Shortest poll interval accepted, so a bad setting can't spin the thread
+const MIN_POLL_INTERVAL_MS: u64 = 100;
+
+/// Input devices that appeared or disappeared since the last check - emitted to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioDevicesChanged {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+}
+
+/// Names of the input devices on the default host
+fn input_device_names() -> Vec<String> {
+    match cpal::default_host().input_devices() {
+        Ok(devices) => devices.filter_map(|device| device.name().ok()).collect(),
+        Err(e) => {
+            warn!("Failed to list input devices: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Background thread that polls the input device list and emits
+/// `audio-devices-changed` when it differs from the previous poll
+///
+/// CPAL has no portable hot-plug notification, so this is how the frontend
+/// learns about a USB microphone being plugged in or removed without calling
+/// `enumerate_recording_devices` itself.
+pub struct DeviceWatcher {
+    stop_tx: Option<mpsc::Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+    poll_interval_ms: Arc<AtomicU64>,
+}
+
+impl DeviceWatcher {
+    pub fn new() -> Self {
+        Self {
+            stop_tx: None,
+            handle: None,
+            poll_interval_ms: Arc::new(AtomicU64::new(DEFAULT_POLL_INTERVAL_MS)),
+        }
+    }
+
+    /// Start polling, replacing any watcher already running
+    pub fn start(&mut self, app_handle: AppHandle) {
+        self.stop();
+
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let poll_interval_ms = self.poll_interval_ms.clone();
+        let handle = thread::spawn(move || {
+            let mut known = input_device_names();
+            let interval = || Duration::from_millis(poll_interval_ms.load(Ordering::Relaxed));
+            // Runs until stopped or the watcher is dropped
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval()) {
+                let current = input_device_names();
+                let added: Vec<String> =
+                    current.iter().filter(|name| !known.contains(name)).cloned().collect();
+                let removed: Vec<String> =
+                    known.iter().filter(|name| !current.contains(name)).cloned().collect();
+                known = current;
+
+                if added.is_empty() && removed.is_empty() {
+                    continue;
+                }
+                info!("Input devices changed: added {:?}, removed {:?}", added, removed);
+                let _ = app_handle.emit(
+                    AUDIO_DEVICES_CHANGED_EVENT,
+                    AudioDevicesChanged { added, removed },
+                );
+            }
+            debug!("Device watcher stopped");
+        });
+
+        self.stop_tx = Some(stop_tx);
+        self.handle = Some(handle);
+    }
+
+    /// Change how often the device list is checked, taking effect after the
+    /// current wait
+    pub fn set_poll_interval_ms(&self, interval_ms: u64) -> u64 {
+        let interval_ms = interval_ms.max(MIN_POLL_INTERVAL_MS);
+        self.poll_interval_ms.store(interval_ms, Ordering::Relaxed);
+        interval_ms
+    }
+
+    pub fn stop(&mut self) {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(());
+        }
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for DeviceWatcher {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
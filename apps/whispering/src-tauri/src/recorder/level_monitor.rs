@@ -0,0 +1,186 @@
+use crate::recorder::audio_level::{measure, AudioLevel};
+use crate::recorder::recorder::{
+    build_typed_stream, find_device, get_optimal_config, resolve_host, Result,
+};
+use cpal::traits::StreamTrait;
+use cpal::{Device, FromSample, Sample, SampleFormat, SizedSample, Stream};
+use std::sync::mpsc;
+use std::thread::{self, JoinHandle};
+use tauri::{AppHandle, Emitter};
+use tracing::{debug, error, info};
+
+/// Event emitted with the current `AudioLevel` while monitoring
+pub const INPUT_LEVEL_EVENT: &str = "input-level";
+
+/// How often level events are emitted (in updates per second)
+const LEVEL_UPDATES_PER_SECOND: u32 = 20;
+
+/// Collects samples until a full update window is ready
+struct LevelMeter {
+    window_len: usize,
+    window: Vec<f32>,
+}
+
+impl LevelMeter {
+    fn new(sample_rate: u32, channels: u16) -> Self {
+        let window_len = (sample_rate / LEVEL_UPDATES_PER_SECOND) as usize * channels as usize;
+        let window_len = window_len.max(1);
+        Self {
+            window_len,
+            window: Vec::with_capacity(window_len),
+        }
+    }
+
+    /// Feed one sample, returning a level once the window is full
+    fn push(&mut self, sample: f32) -> Option<AudioLevel> {
+        self.window.push(sample);
+        if self.window.len() < self.window_len {
+            return None;
+        }
+        // Draining keeps the allocation for the next window
+        Some(measure(self.window.drain(..)))
+    }
+}
+
+/// Live input level monitor that never writes to disk
+///
+/// Opens its own stream on the chosen device and only computes levels, so the
+/// user can position their mic before committing to a recording. Like the
+/// recorder, the stream is owned by a dedicated worker thread (required for
+/// macOS).
+pub struct LevelMonitor {
+    stop_tx: Option<mpsc::Sender<()>>,
+    worker_handle: Option<JoinHandle<()>>,
+}
+
+impl LevelMonitor {
+    pub fn new() -> Self {
+        Self {
+            stop_tx: None,
+            worker_handle: None,
+        }
+    }
+
+    /// Start monitoring a device, replacing any monitor already running
+    pub fn start(
+        &mut self,
+        device_name: String,
+        host_name: Option<String>,
+        app_handle: AppHandle,
+    ) -> Result<()> {
+        self.stop();
+
+        let host = resolve_host(host_name.as_deref())?;
+        let device = find_device(&host, &device_name)?;
+
+        // Same negotiation as the recorder so the meter reflects what will be recorded
+        let config = get_optimal_config(&device, None)?;
+        let sample_format = config.sample_format();
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels();
+        let stream_config = cpal::StreamConfig {
+            channels,
+            sample_rate: cpal::SampleRate(sample_rate),
+            buffer_size: cpal::BufferSize::Default,
+        };
+
+        let (stop_tx, stop_rx) = mpsc::channel::<()>();
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<()>>();
+
+        let worker = thread::spawn(move || {
+            let meter = LevelMeter::new(sample_rate, channels);
+            let stream = match build_level_stream(
+                &device,
+                &stream_config,
+                sample_format,
+                meter,
+                app_handle,
+            )
+            .and_then(|stream| {
+                stream
+                    .play()
+                    .map_err(|e| format!("Failed to start level monitor stream: {}", e))?;
+                Ok(stream)
+            }) {
+                Ok(stream) => {
+                    let _ = ready_tx.send(Ok(()));
+                    stream
+                }
+                Err(e) => {
+                    error!("{}", e);
+                    let _ = ready_tx.send(Err(e));
+                    return;
+                }
+            };
+
+            // Block until asked to stop (or the monitor is dropped)
+            let _ = stop_rx.recv();
+            drop(stream);
+            debug!("Level monitor stream closed");
+        });
+
+        // Surface stream errors to the caller instead of failing silently
+        ready_rx
+            .recv()
+            .map_err(|e| format!("Level monitor worker exited unexpectedly: {}", e))??;
+
+        self.stop_tx = Some(stop_tx);
+        self.worker_handle = Some(worker);
+
+        info!(
+            "Level monitor started: {} Hz, {} channels, device: {}",
+            sample_rate, channels, device_name
+        );
+
+        Ok(())
+    }
+
+    /// Stop monitoring; does nothing if no monitor is running
+    pub fn stop(&mut self) {
+        if let Some(tx) = self.stop_tx.take() {
+            let _ = tx.send(());
+        }
+
+        if let Some(handle) = self.worker_handle.take() {
+            let _ = handle.join();
+            info!("Level monitor stopped");
+        }
+    }
+}
+
+/// Build an input stream that only feeds the level meter
+fn build_level_stream(
+    device: &Device,
+    config: &cpal::StreamConfig,
+    sample_format: SampleFormat,
+    meter: LevelMeter,
+    app_handle: AppHandle,
+) -> Result<Stream> {
+    match sample_format {
+        SampleFormat::F32 => build_typed_stream(device, config, level_callback::<f32>(meter, app_handle)),
+        SampleFormat::I16 => build_typed_stream(device, config, level_callback::<i16>(meter, app_handle)),
+        SampleFormat::U16 => build_typed_stream(device, config, level_callback::<u16>(meter, app_handle)),
+        _ => Err(format!("Unsupported sample format: {:?}", sample_format)),
+    }
+}
+
+/// Data callback that converts samples to f32 and emits levels as windows fill
+fn level_callback<T>(mut meter: LevelMeter, app_handle: AppHandle) -> impl FnMut(&[T]) + Send + 'static
+where
+    T: SizedSample,
+    f32: FromSample<T>,
+{
+    move |data: &[T]| {
+        for &sample in data {
+            if let Some(level) = meter.push(sample.to_sample::<f32>()) {
+                let _ = app_handle.emit(INPUT_LEVEL_EVENT, level);
+            }
+        }
+    }
+}
+
+impl Drop for LevelMonitor {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
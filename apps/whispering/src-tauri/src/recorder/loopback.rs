@@ -0,0 +1,162 @@
+use crate::recorder::recorder::Result;
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+use crate::recorder::recorder::resolve_host;
+#[cfg(not(target_os = "windows"))]
+use crate::recorder::recorder::{find_device, get_optimal_config};
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+use cpal::traits::{DeviceTrait, HostTrait};
+use cpal::Device;
+
+/// Name fragments of virtual devices that carry system audio on macOS
+#[cfg(target_os = "macos")]
+const MACOS_LOOPBACK_DEVICES: [&str; 3] = ["blackhole", "soundflower", "loopback audio"];
+
+/// Names of the sources system audio can be recorded from
+///
+/// - Windows: output devices, recorded with WASAPI loopback capture (CPAL
+///   does this when an input stream is built on an output device)
+/// - macOS: virtual devices such as BlackHole or Soundflower that system
+///   output is routed to, as there is no loopback API
+/// - Linux: PulseAudio (or PipeWire's Pulse server) monitor sources, recorded
+///   through the ALSA `pulse` device
+///
+/// Empty when the platform supports loopback but no source is set up (e.g.
+/// no BlackHole install on macOS); other platforms return an error.
+pub fn enumerate_loopback_devices(host_name: Option<&str>) -> Result<Vec<String>> {
+    #[cfg(target_os = "windows")]
+    {
+        let host = resolve_host(host_name)?;
+        let devices = host
+            .output_devices()
+            .map_err(|e| format!("Failed to get output devices: {}", e))?
+            .filter_map(|device| device.name().ok())
+            .collect();
+        Ok(devices)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let host = resolve_host(host_name)?;
+        let devices = host
+            .input_devices()
+            .map_err(|e| format!("Failed to get input devices: {}", e))?
+            .filter_map(|device| device.name().ok())
+            .filter(|name| is_macos_loopback_device(name))
+            .collect();
+        Ok(devices)
+    }
+    #[cfg(target_os = "linux")]
+    {
+        let _ = host_name;
+        pulse_monitor_sources()
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+    {
+        let _ = host_name;
+        Err("System audio recording is not supported on this platform".to_string())
+    }
+}
+
+/// Find the device to record system audio from `device_name` with
+///
+/// "default" means the default output device on Windows and the first
+/// loopback source elsewhere.
+pub(crate) fn find_loopback_device(host: &cpal::Host, device_name: &str) -> Result<Device> {
+    #[cfg(target_os = "windows")]
+    {
+        if device_name.eq_ignore_ascii_case("default") {
+            return host
+                .default_output_device()
+                .ok_or_else(|| "No default output device to record system audio from".to_string());
+        }
+        host.output_devices()
+            .map_err(|e| e.to_string())?
+            .find(|device| device.name().is_ok_and(|name| name == device_name))
+            .ok_or_else(|| format!("Output device '{}' not found", device_name))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        let sources = enumerate_loopback_devices(None)?;
+        let source = if device_name.eq_ignore_ascii_case("default") {
+            sources.first()
+        } else {
+            sources.iter().find(|source| *source == device_name)
+        }
+        .ok_or_else(|| no_loopback_source_error(device_name))?;
+
+        #[cfg(target_os = "linux")]
+        {
+            // The ALSA pulse plugin records from the source PulseAudio clients
+            // default to, which PULSE_SOURCE overrides
+            std::env::set_var("PULSE_SOURCE", source);
+            find_device(host, "pulse").or_else(|_| find_device(host, "default"))
+        }
+        #[cfg(not(target_os = "linux"))]
+        {
+            find_device(host, source)
+        }
+    }
+}
+
+/// Stream config for recording from a loopback device
+///
+/// WASAPI loopback only delivers the output device's mix format; elsewhere
+/// the source is an ordinary input device.
+pub(crate) fn loopback_config(
+    device: &Device,
+    preferred_sample_rate: Option<u32>,
+) -> Result<cpal::SupportedStreamConfig> {
+    #[cfg(target_os = "windows")]
+    {
+        let _ = preferred_sample_rate;
+        device
+            .default_output_config()
+            .map_err(|e| format!("Failed to get output device config: {}", e))
+    }
+    #[cfg(not(target_os = "windows"))]
+    {
+        get_optimal_config(device, preferred_sample_rate)
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn no_loopback_source_error(device_name: &str) -> String {
+    let hint = if cfg!(target_os = "macos") {
+        "install a virtual audio device such as BlackHole and route system output to it"
+    } else {
+        "a PulseAudio or PipeWire monitor source is required"
+    };
+    if device_name.eq_ignore_ascii_case("default") {
+        format!("No system audio source found; {}", hint)
+    } else {
+        format!("System audio source '{}' not found; {}", device_name, hint)
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn is_macos_loopback_device(name: &str) -> bool {
+    let name = name.to_lowercase();
+    MACOS_LOOPBACK_DEVICES.iter().any(|fragment| name.contains(fragment))
+}
+
+/// Monitor sources reported by `pactl`, one per output sink
+#[cfg(target_os = "linux")]
+fn pulse_monitor_sources() -> Result<Vec<String>> {
+    let output = std::process::Command::new("pactl")
+        .args(["list", "short", "sources"])
+        .output()
+        .map_err(|e| format!("Failed to run pactl (is PulseAudio or PipeWire running?): {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "pactl failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    // Lines are `<index>\t<name>\t<module>\t<format>\t<state>`
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| line.split('\t').nth(1))
+        .filter(|name| name.ends_with(".monitor"))
+        .map(str::to_string)
+        .collect())
+}
@@ -1,11 +1,26 @@
+pub mod agc;
+pub mod audio_level;
 pub mod commands;
+pub mod device_watcher;
+pub mod level_monitor;
+pub mod loopback;
+pub mod noise_gate;
+pub mod rate_monitor;
 pub mod recorder;
+pub mod storage;
+pub mod summary;
 pub mod wav_writer;
 
 // Export everything from commands for easy access
 pub use commands::{
-    cancel_recording, close_recording_session, enumerate_recording_devices,
-    get_current_recording_id, init_recording_session, start_recording, stop_recording, AppData,
+    cancel_recording, check_disk_space, clear_noise_gate, close_recording_session, disable_agc,
+    enable_agc, enumerate_hosts, enumerate_loopback_devices, enumerate_recording_devices,
+    enumerate_recording_devices_with_caps, estimate_storage, get_current_recording_id,
+    get_recording_device_error, init_recording_session, list_recording_sessions,
+    normalize_recording, pause_recording, preview_recording_config, push_to_talk_end,
+    push_to_talk_start, recover_recording, resume_recording, set_device_poll_interval_ms,
+    set_noise_gate, start_level_monitor, start_recording, stop_level_monitor, stop_recording,
+    AppData,
 };
 
 // Export key types from recorder
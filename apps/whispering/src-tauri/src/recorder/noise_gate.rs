@@ -0,0 +1,65 @@
+use std::sync::{Arc, Mutex};
+
+/// Shortest attack or release accepted, so the gate never switches within a sample
+const MIN_RAMP_MS: f32 = 0.1;
+
+/// Noise gate applied to captured audio before it is written, shared with the
+/// stream callback; `None` when no gate is set
+pub type SharedNoiseGate = Arc<Mutex<Option<NoiseGate>>>;
+
+pub fn new_shared_noise_gate() -> SharedNoiseGate {
+    Arc::new(Mutex::new(None))
+}
+
+/// Silences input quieter than a threshold while recording
+///
+/// The level of each frame (the loudest channel) drives an envelope that
+/// falls off over the release time, so the gate doesn't chatter between the
+/// peaks of a waveform. While the envelope is at or above the threshold the
+/// gain ramps up to 1 over `attack_ms`; below it the gain ramps down to 0 over
+/// `release_ms`, avoiding the clicks of an abrupt cut.
+#[derive(Debug, Clone)]
+pub struct NoiseGate {
+    attack_ms: f32,
+    release_ms: f32,
+    threshold: f32,
+    envelope: f32,
+    gain: f32,
+}
+
+impl NoiseGate {
+    pub fn new(threshold_dbfs: f32, attack_ms: f32, release_ms: f32) -> Self {
+        Self {
+            attack_ms: attack_ms.max(MIN_RAMP_MS),
+            release_ms: release_ms.max(MIN_RAMP_MS),
+            threshold: 10f32.powf(threshold_dbfs / 20.0),
+            envelope: 0.0,
+            // Start closed so leading background noise is gated too
+            gain: 0.0,
+        }
+    }
+
+    /// Gate interleaved samples in place
+    pub fn process(&mut self, samples: &mut [f32], sample_rate: u32, channels: u16) {
+        let frames_per_ms = sample_rate as f32 / 1000.0;
+        let attack_step = 1.0 / (self.attack_ms * frames_per_ms).max(1.0);
+        let release_step = 1.0 / (self.release_ms * frames_per_ms).max(1.0);
+        // Envelope decays by 60 dB over the release time
+        let envelope_decay = 0.001f32.powf(release_step);
+
+        for frame in samples.chunks_mut(channels.max(1) as usize) {
+            let level = frame.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+            self.envelope = level.max(self.envelope * envelope_decay);
+
+            self.gain = if self.envelope >= self.threshold {
+                (self.gain + attack_step).min(1.0)
+            } else {
+                (self.gain - release_step).max(0.0)
+            };
+
+            for sample in frame {
+                *sample *= self.gain;
+            }
+        }
+    }
+}
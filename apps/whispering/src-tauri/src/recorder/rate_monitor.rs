@@ -0,0 +1,102 @@
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+use tracing::warn;
+
+/// Length of each measurement window, in seconds
+const WINDOW_SECS: f64 = 2.0;
+
+/// Relative difference between measured and negotiated rate that counts as drift
+const DRIFT_TOLERANCE: f64 = 0.1;
+
+/// The device is delivering samples at a different rate than negotiated
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SampleRateDrift {
+    pub expected_rate: u32,
+    pub measured_rate: u32,
+}
+
+/// Called from the audio callback when drift is first detected
+///
+/// Shared so a session can hand it to the monitor of a rebuilt stream.
+pub type DriftCallback = Arc<dyn Fn(SampleRateDrift) + Send + Sync>;
+
+/// Detects devices whose effective sample rate changes mid-stream
+///
+/// Some devices (notably Bluetooth headsets switching between A2DP and HFP
+/// profiles) silently change their rate while the stream keeps running. The WAV
+/// header is fixed at the negotiated rate, so such a file plays at the wrong
+/// speed and its reported duration is wrong. We compare the frames delivered
+/// in each window against wall-clock time; callback jitter averages out over a
+/// window, while a real rate change shows up as a large, sustained deviation.
+pub struct RateMonitor {
+    expected_rate: u32,
+    channels: usize,
+    window_start: Option<Instant>,
+    window_frames: u64,
+    drifting: bool,
+    drift_detected: Arc<AtomicBool>,
+    on_drift: Option<DriftCallback>,
+}
+
+impl RateMonitor {
+    /// `drift_detected` is set once drift has been seen and never cleared
+    pub fn new(
+        expected_rate: u32,
+        channels: u16,
+        drift_detected: Arc<AtomicBool>,
+        on_drift: Option<DriftCallback>,
+    ) -> Self {
+        Self {
+            expected_rate,
+            channels: channels.max(1) as usize,
+            window_start: None,
+            window_frames: 0,
+            drifting: false,
+            drift_detected,
+            on_drift,
+        }
+    }
+
+    /// Account for one callback buffer of interleaved samples
+    pub fn observe(&mut self, num_samples: usize) {
+        let now = Instant::now();
+        let Some(window_start) = self.window_start else {
+            // Timing starts at the first buffer; its samples were captured before it
+            self.window_start = Some(now);
+            return;
+        };
+
+        self.window_frames += (num_samples / self.channels) as u64;
+        let elapsed = now.duration_since(window_start).as_secs_f64();
+        if elapsed < WINDOW_SECS {
+            return;
+        }
+
+        let measured_rate = self.window_frames as f64 / elapsed;
+        let deviation = (measured_rate - self.expected_rate as f64).abs() / self.expected_rate as f64;
+        let drifting = deviation > DRIFT_TOLERANCE;
+
+        // Report on the transition into drift rather than every window
+        if drifting && !self.drifting {
+            let drift = SampleRateDrift {
+                expected_rate: self.expected_rate,
+                measured_rate: measured_rate.round() as u32,
+            };
+            warn!(
+                "Device is delivering ~{} Hz instead of {} Hz; recording duration and playback speed will be unreliable",
+                drift.measured_rate, drift.expected_rate
+            );
+            self.drift_detected.store(true, Ordering::Relaxed);
+            if let Some(on_drift) = &self.on_drift {
+                on_drift(drift);
+            }
+        }
+        self.drifting = drifting;
+
+        self.window_start = Some(now);
+        self.window_frames = 0;
+    }
+}
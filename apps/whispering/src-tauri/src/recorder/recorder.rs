@@ -1,16 +1,127 @@
-use crate::recorder::wav_writer::WavWriter;
+use crate::recorder::agc::{new_shared_agc, AgcState, SharedAgc};
+use crate::recorder::audio_level::{
+    new_level_window, push_samples, AudioLevelCallback, AudioLevelTask, LevelWindow,
+};
+use crate::recorder::loopback::{find_loopback_device, loopback_config};
+use crate::recorder::noise_gate::{new_shared_noise_gate, NoiseGate, SharedNoiseGate};
+use crate::recorder::rate_monitor::{DriftCallback, RateMonitor};
+use crate::recorder::summary::RecordingSummary;
+use crate::recorder::wav_writer::{
+    i16_to_f32, u16_to_f32, WavWriter, WavWriterConfig, WavWriterStatus,
+};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, SampleFormat, Stream};
-use serde::Serialize;
+use cpal::{Device, FromSample, SampleFormat, SizedSample, Stream};
+use serde::{Deserialize, Serialize};
+use std::io;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{mpsc, Arc, Mutex};
 use std::thread::{self, JoinHandle};
-use tracing::{debug, error, info};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tracing::{debug, error, info, warn};
 
 /// Simple result type using String for errors
 pub type Result<T> = std::result::Result<T, String>;
 
+/// Free space a session needs on the output volume before it will start
+const MIN_FREE_DISK_SPACE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// How often a session following the default device checks whether it changed
+const DEFAULT_DEVICE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Called from the worker thread with the new device's name after the stream
+/// has been moved to a new default device
+pub type DeviceSwitchCallback = Box<dyn Fn(String) + Send>;
+
+/// Called from the audio thread with the message of the first stream error
+/// (e.g. the device being unplugged) in a session
+pub type DeviceErrorCallback = Arc<dyn Fn(String) + Send + Sync>;
+
+/// Where a session's streams report errors, shared with the stream callbacks
+#[derive(Clone, Default)]
+struct DeviceErrorSink {
+    occurred: Arc<AtomicBool>,
+    message: Arc<Mutex<Option<String>>>,
+    on_error: Option<DeviceErrorCallback>,
+}
+
+impl DeviceErrorSink {
+    fn new(on_error: Option<DeviceErrorCallback>) -> Self {
+        Self {
+            on_error,
+            ..Self::default()
+        }
+    }
+
+    /// Record a stream error; only the first one is kept and reported
+    fn report(&self, err: cpal::StreamError) {
+        error!("Audio stream error: {}", err);
+        if self.occurred.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let message = err.to_string();
+        if let Ok(mut stored) = self.message.lock() {
+            *stored = Some(message.clone());
+        }
+        if let Some(on_error) = &self.on_error {
+            on_error(message);
+        }
+    }
+
+    fn get(&self) -> Option<String> {
+        if !self.occurred.load(Ordering::Acquire) {
+            return None;
+        }
+        self.message.lock().ok().and_then(|message| message.clone())
+    }
+}
+
+/// How a session decides which captured audio goes into the file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RecordingMode {
+    /// Everything between start and stop is written
+    #[default]
+    Manual,
+    /// Walkie-talkie style: while recording, audio is only written between
+    /// `push_start` and `push_end`, so one file collects several utterances
+    /// without reopening the stream for each
+    PushToTalk,
+}
+
+/// Notifications a session sends while its stream is open
+#[derive(Default)]
+pub struct SessionCallbacks {
+    /// The device started delivering samples at a different rate than negotiated
+    pub on_rate_drift: Option<DriftCallback>,
+    /// The session moved to a new default device (see `follow_default_device`)
+    pub on_device_switched: Option<DeviceSwitchCallback>,
+    /// Live input level, reported every 50 ms while the session is open
+    pub on_audio_level: Option<AudioLevelCallback>,
+    /// The stream failed, e.g. because the device was unplugged
+    pub on_device_error: Option<DeviceErrorCallback>,
+}
+
+/// What a session records and how, passed to `RecorderState::init_session`
+#[derive(Debug, Clone)]
+pub struct SessionOptions {
+    /// Input device name or "default"; a system audio source with `loopback`
+    pub device_name: String,
+    pub output_folder: PathBuf,
+    /// The recording is written to `<output_folder>/<recording_id>.wav`
+    pub recording_id: String,
+    /// Used if the device supports it; otherwise the closest supported rate
+    pub preferred_sample_rate: Option<u32>,
+    pub writer_config: WavWriterConfig,
+    /// Audio backend (see `available_host_names`); CPAL's default when unset
+    pub host_name: Option<String>,
+    /// Move to the new system default device if it changes before recording
+    pub follow_default_device: bool,
+    /// Capture system audio (see `enumerate_loopback_devices`)
+    pub loopback: bool,
+    pub mode: RecordingMode,
+}
+
 /// Audio recording metadata - returned to frontend
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -20,6 +131,43 @@ pub struct AudioRecording {
     pub channels: u16,
     pub duration_seconds: f32,
     pub file_path: Option<String>, // Path to the WAV file
+    /// Unix time (ms) at which the first sample was captured, for aligning
+    /// recordings made by separate sessions
+    pub first_sample_time_ms: Option<u64>,
+    /// Peak/RMS levels, clipping and silence ratio, computed while recording
+    pub summary: Option<RecordingSummary>,
+    /// The device's sample rate changed mid-stream, so duration and playback
+    /// speed of the file can't be trusted
+    pub sample_rate_unreliable: bool,
+    /// Time spent paused; not included in `duration_seconds`
+    pub paused_seconds: f32,
+    /// The stream failed during the session (e.g. the device was unplugged),
+    /// so the recording may be truncated
+    pub device_error: Option<String>,
+}
+
+/// Stream configuration chosen for a device - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingConfig {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Sample format delivered by the device ("f32", "i16" or "u16")
+    pub sample_format: String,
+}
+
+/// What a recording device supports - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCapabilities {
+    pub name: String,
+    /// Sorted; devices reporting a continuous range list the common rates in it
+    pub supported_sample_rates: Vec<u32>,
+    pub supported_channels: Vec<u16>,
+    /// Sample formats such as "f32" or "i16"
+    pub supported_formats: Vec<String>,
+    /// Rate of the device's default input config, 0 if it has none
+    pub default_sample_rate: u32,
 }
 
 /// Simple recorder commands for worker thread communication
@@ -36,6 +184,20 @@ pub struct RecorderState {
     worker_handle: Option<JoinHandle<()>>,
     writer: Option<Arc<Mutex<WavWriter>>>,
     is_recording: Arc<AtomicBool>,
+    /// Audio is only written while this is set; always set in manual mode
+    is_pushing: Arc<AtomicBool>,
+    mode: RecordingMode,
+    rate_drift: Arc<AtomicBool>,
+    device_error: DeviceErrorSink,
+    /// Applied to audio before it is written; kept across sessions
+    noise_gate: SharedNoiseGate,
+    /// Applied after the noise gate; kept across sessions
+    agc: SharedAgc,
+    /// When the current pause began, while paused
+    paused_at: Option<Instant>,
+    /// Total time spent paused in completed pauses of the current recording
+    paused_total: Duration,
+    level_task: Option<AudioLevelTask>,
     sample_rate: u32,
     channels: u16,
     file_path: Option<PathBuf>,
@@ -48,15 +210,24 @@ impl RecorderState {
             worker_handle: None,
             writer: None,
             is_recording: Arc::new(AtomicBool::new(false)),
+            is_pushing: Arc::new(AtomicBool::new(true)),
+            mode: RecordingMode::Manual,
+            rate_drift: Arc::new(AtomicBool::new(false)),
+            device_error: DeviceErrorSink::default(),
+            noise_gate: new_shared_noise_gate(),
+            agc: new_shared_agc(),
+            paused_at: None,
+            paused_total: Duration::ZERO,
+            level_task: None,
             sample_rate: 0,
             channels: 0,
             file_path: None,
         }
     }
 
-    /// List available recording devices by name
-    pub fn enumerate_devices(&self) -> Result<Vec<String>> {
-        let host = cpal::default_host();
+    /// List available recording devices by name (on the default host unless one is named)
+    pub fn enumerate_devices(&self, host_name: Option<&str>) -> Result<Vec<String>> {
+        let host = resolve_host(host_name)?;
         let devices = host
             .input_devices()
             .map_err(|e| format!("Failed to get input devices: {}", e))?
@@ -67,31 +238,65 @@ impl RecorderState {
     }
 
     /// Initialize recording session - creates stream and WAV writer
+    ///
+    /// With `follow_default_device` and the "default" device, the stream is
+    /// rebuilt on the new default device when it changes before recording
+    /// starts. `callbacks` are invoked from the audio and worker threads. In
+    /// `PushToTalk` mode nothing is written until `push_start`.
     pub fn init_session(
         &mut self,
-        device_name: String,
-        output_folder: PathBuf,
-        recording_id: String,
-        preferred_sample_rate: Option<u32>,
+        options: SessionOptions,
+        callbacks: SessionCallbacks,
     ) -> Result<()> {
+        let SessionOptions {
+            device_name,
+            output_folder,
+            recording_id,
+            preferred_sample_rate,
+            writer_config,
+            host_name,
+            follow_default_device,
+            loopback,
+            mode,
+        } = options;
+
+        // Refuse to start a recording that would soon run out of space. The folder
+        // may not exist yet on a first recording, so create it before measuring
+        std::fs::create_dir_all(&output_folder)
+            .map_err(|e| format!("Failed to create output folder {:?}: {}", output_folder, e))?;
+        let available = fs2::available_space(&output_folder)
+            .map_err(|e| format!("Failed to check disk space in {:?}: {}", output_folder, e))?;
+        if available < MIN_FREE_DISK_SPACE_BYTES {
+            return Err(format!(
+                "Insufficient disk space: only {} MB available",
+                available / (1024 * 1024)
+            ));
+        }
+
         // Clean up any existing session
         self.close_session()?;
 
         // Create file path
         let file_path = output_folder.join(format!("{}.wav", recording_id));
 
-        // Find the device
-        let host = cpal::default_host();
-        let device = find_device(&host, &device_name)?;
-
-        // Get optimal config for voice with optional preferred sample rate
-        let config = get_optimal_config(&device, preferred_sample_rate)?;
+        // Find the device on the requested backend
+        let host = resolve_host(host_name.as_deref())?;
+        let (device, config) = if loopback {
+            let device = find_loopback_device(&host, &device_name)?;
+            let config = loopback_config(&device, preferred_sample_rate)?;
+            (device, config)
+        } else {
+            let device = find_device(&host, &device_name)?;
+            // Get optimal config for voice with optional preferred sample rate
+            let config = get_optimal_config(&device, preferred_sample_rate)?;
+            (device, config)
+        };
         let sample_format = config.sample_format();
         let sample_rate = config.sample_rate().0;
         let channels = config.channels();
 
         // Create WAV writer
-        let writer = WavWriter::new(file_path.clone(), sample_rate, channels)
+        let writer = WavWriter::new(file_path.clone(), sample_rate, channels, writer_config)
             .map_err(|e| format!("Failed to create WAV file: {}", e))?;
         let writer = Arc::new(Mutex::new(writer));
 
@@ -106,22 +311,61 @@ impl RecorderState {
         self.is_recording = Arc::new(AtomicBool::new(false));
         let is_recording = self.is_recording.clone();
 
+        // Manual sessions write whenever recording; push-to-talk waits for a push
+        self.mode = mode;
+        self.is_pushing = Arc::new(AtomicBool::new(mode == RecordingMode::Manual));
+
+        // Watch for devices that change rate mid-stream
+        self.rate_drift = Arc::new(AtomicBool::new(false));
+        let rate_monitor = RateMonitor::new(
+            sample_rate,
+            channels,
+            self.rate_drift.clone(),
+            callbacks.on_rate_drift.clone(),
+        );
+
+        // Remember stream failures such as the device being unplugged
+        self.device_error = DeviceErrorSink::new(callbacks.on_device_error);
+
+        // Latest samples for the live level meter
+        let level_window = new_level_window();
+
+        // Handed to the stream, and to any stream that replaces it
+        let shared = StreamShared {
+            is_recording: is_recording.clone(),
+            is_pushing: self.is_pushing.clone(),
+            writer: writer.clone(),
+            level_window: level_window.clone(),
+            device_error: self.device_error.clone(),
+            noise_gate: self.noise_gate.clone(),
+            agc: self.agc.clone(),
+        };
+
+        // Only the "default" input device can follow the system default
+        let follows_default = !loopback && device_name.eq_ignore_ascii_case("default");
+        let follower = (follow_default_device && follows_default)
+            .then(|| DefaultDeviceFollower {
+                host_name,
+                current_device: device.name().unwrap_or_default(),
+                preferred_sample_rate,
+                shared: shared.clone(),
+                rate_drift: self.rate_drift.clone(),
+                on_rate_drift: callbacks.on_rate_drift,
+                on_switched: callbacks.on_device_switched,
+            });
+
         // Create command channel for worker thread
         let (cmd_tx, cmd_rx) = mpsc::channel();
 
-        // Clone for the worker thread
-        let writer_clone = writer.clone();
-        let is_recording_clone = is_recording.clone();
-
         // Create the worker thread that owns the stream
         let worker = thread::spawn(move || {
             // Build the stream IN this thread (required for macOS)
-            let stream = match build_input_stream(
+            let mut stream = match build_input_stream(
                 &device,
                 &stream_config,
                 sample_format,
-                is_recording_clone,
-                writer_clone,
+                shared,
+                rate_monitor,
             ) {
                 Ok(s) => s,
                 Err(e) => {
@@ -140,19 +384,35 @@ impl RecorderState {
 
             // Keep thread alive by waiting for commands
             // This blocks but is responsive - no sleeping!
+            let mut follower = follower;
             loop {
-                match cmd_rx.recv() {
-                    Ok(RecorderCmd::Start(reply_tx)) => {
+                let cmd = match &mut follower {
+                    // Wake up periodically to check the default device
+                    Some(follower) => match cmd_rx.recv_timeout(DEFAULT_DEVICE_POLL_INTERVAL) {
+                        Ok(cmd) => cmd,
+                        Err(mpsc::RecvTimeoutError::Timeout) => {
+                            if let Some(new_stream) = follower.poll() {
+                                stream = new_stream;
+                            }
+                            continue;
+                        }
+                        Err(mpsc::RecvTimeoutError::Disconnected) => RecorderCmd::Shutdown,
+                    },
+                    None => cmd_rx.recv().unwrap_or(RecorderCmd::Shutdown),
+                };
+
+                match cmd {
+                    RecorderCmd::Start(reply_tx) => {
                         is_recording.store(true, Ordering::Relaxed);
                         info!("Recording started");
                         let _ = reply_tx.send(()); // Confirm command processed
                     }
-                    Ok(RecorderCmd::Stop(reply_tx)) => {
+                    RecorderCmd::Stop(reply_tx) => {
                         is_recording.store(false, Ordering::Relaxed);
                         info!("Recording stopped");
                         let _ = reply_tx.send(()); // Confirm command processed
                     }
-                    Ok(RecorderCmd::Shutdown) | Err(_) => {
+                    RecorderCmd::Shutdown => {
                         info!("Shutting down audio worker");
                         break;
                     }
@@ -162,6 +422,9 @@ impl RecorderState {
         });
 
         // Store everything
+        self.level_task = callbacks
+            .on_audio_level
+            .map(|on_level| AudioLevelTask::start(level_window, on_level));
         self.cmd_tx = Some(cmd_tx);
         self.worker_handle = Some(worker);
         self.writer = Some(writer);
@@ -179,6 +442,8 @@ impl RecorderState {
 
     /// Start recording - send command to worker thread and wait for confirmation
     pub fn start_recording(&mut self) -> Result<()> {
+        self.paused_at = None;
+        self.paused_total = Duration::ZERO;
         if let Some(tx) = &self.cmd_tx {
             let (reply_tx, reply_rx) = mpsc::channel();
             tx.send(RecorderCmd::Start(reply_tx))
@@ -192,6 +457,115 @@ impl RecorderState {
         Ok(())
     }
 
+    /// Pause recording without finalizing the file; a no-op if already paused
+    ///
+    /// The stream keeps running but its samples are dropped, so the file (and
+    /// its duration) only ever contains captured audio.
+    pub fn pause_recording(&mut self) -> Result<()> {
+        if self.paused_at.is_some() {
+            return Ok(());
+        }
+        if !self.is_recording.load(Ordering::Acquire) {
+            return Err("Not recording".to_string());
+        }
+
+        self.send_and_wait(RecorderCmd::Stop, "pause")?;
+        self.paused_at = Some(Instant::now());
+        info!("Recording paused");
+        Ok(())
+    }
+
+    /// Resume a paused recording, appending to the same file; a no-op if not paused
+    pub fn resume_recording(&mut self) -> Result<()> {
+        let Some(paused_at) = self.paused_at else {
+            return Ok(());
+        };
+
+        self.send_and_wait(RecorderCmd::Start, "resume")?;
+        self.paused_total += paused_at.elapsed();
+        self.paused_at = None;
+        info!("Recording resumed");
+        Ok(())
+    }
+
+    /// Start writing audio in a push-to-talk session (the talk button went down)
+    pub fn push_start(&self) -> Result<()> {
+        self.set_pushing(true)
+    }
+
+    /// Stop writing audio in a push-to-talk session; the file stays open for
+    /// the next push until `stop_recording`
+    pub fn push_end(&self) -> Result<()> {
+        self.set_pushing(false)
+    }
+
+    fn set_pushing(&self, pushing: bool) -> Result<()> {
+        if self.cmd_tx.is_none() {
+            return Err("No recording session initialized".to_string());
+        }
+        if self.mode != RecordingMode::PushToTalk {
+            return Err("Session is not in push-to-talk mode".to_string());
+        }
+        if !self.is_recording.load(Ordering::Acquire) {
+            return Err("Not recording".to_string());
+        }
+
+        self.is_pushing.store(pushing, Ordering::Release);
+        debug!("Push-to-talk {}", if pushing { "started" } else { "ended" });
+        Ok(())
+    }
+
+    /// Send a command to the worker thread and wait for it to be processed
+    fn send_and_wait(&self, cmd: fn(mpsc::Sender<()>) -> RecorderCmd, action: &str) -> Result<()> {
+        let tx = self
+            .cmd_tx
+            .as_ref()
+            .ok_or_else(|| "No recording session initialized".to_string())?;
+        let (reply_tx, reply_rx) = mpsc::channel();
+        tx.send(cmd(reply_tx))
+            .map_err(|e| format!("Failed to send {} command: {}", action, e))?;
+        reply_rx
+            .recv()
+            .map_err(|e| format!("Failed to receive {} confirmation: {}", action, e))
+    }
+
+    /// Time spent paused in the current recording, including an ongoing pause
+    fn paused_duration(&self) -> Duration {
+        self.paused_total + self.paused_at.map(|at| at.elapsed()).unwrap_or_default()
+    }
+
+    /// Gate input quieter than the gate's threshold while recording, or remove
+    /// the gate with `None`
+    ///
+    /// Takes effect immediately, including in a recording in progress.
+    pub fn set_noise_gate(&self, noise_gate: Option<NoiseGate>) -> Result<()> {
+        *self
+            .noise_gate
+            .lock()
+            .map_err(|e| format!("Failed to lock noise gate: {}", e))? = noise_gate;
+        Ok(())
+    }
+
+    /// Bring quiet input up toward a target level while recording, or turn
+    /// automatic gain control off with `None`
+    ///
+    /// Takes effect immediately, including in a recording in progress.
+    pub fn set_agc(&self, agc: Option<AgcState>) -> Result<()> {
+        *self
+            .agc
+            .lock()
+            .map_err(|e| format!("Failed to lock AGC: {}", e))? = agc;
+        Ok(())
+    }
+
+    /// Error the stream reported during this session, if any
+    ///
+    /// Set when e.g. the device is unplugged mid-recording; audio captured
+    /// after that point is missing from the file.
+    pub fn get_device_error(&self) -> Option<String> {
+        self.device_error.get()
+    }
+
     /// Stop recording - return file info
     pub fn stop_recording(&mut self) -> Result<AudioRecording> {
         // Send stop command to worker thread and wait for confirmation
@@ -205,22 +579,40 @@ impl RecorderState {
         }
 
         // Finalize the WAV file and get metadata
-        let (sample_rate, channels, duration) = if let Some(writer) = &self.writer {
-            let mut w = writer
-                .lock()
-                .map_err(|e| format!("Failed to lock writer: {}", e))?;
-            w.finalize()
-                .map_err(|e| format!("Failed to finalize WAV: {}", e))?;
-            w.get_metadata()
-        } else {
-            (self.sample_rate, self.channels, 0.0)
-        };
+        let (sample_rate, channels, duration, first_sample_time, summary) =
+            if let Some(writer) = &self.writer {
+                let mut w = writer
+                    .lock()
+                    .map_err(|e| format!("Failed to lock writer: {}", e))?;
+                w.finalize()
+                    .map_err(|e| format!("Failed to finalize WAV: {}", e))?;
+                let (sample_rate, channels, duration) = w.get_metadata();
+                (
+                    sample_rate,
+                    channels,
+                    duration,
+                    w.get_first_sample_time(),
+                    Some(w.get_summary()),
+                )
+            } else {
+                (self.sample_rate, self.channels, 0.0, None, None)
+            };
+        let first_sample_time_ms = first_sample_time
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_millis() as u64);
 
         let file_path = self
             .file_path
             .as_ref()
             .map(|p| p.to_string_lossy().to_string());
 
+        let paused_seconds = self.paused_duration().as_secs_f32();
+        self.paused_at = None;
+        self.paused_total = Duration::ZERO;
+        if self.mode == RecordingMode::PushToTalk {
+            self.is_pushing.store(false, Ordering::Release);
+        }
+
         info!("Recording stopped: {:.2}s, file: {:?}", duration, file_path);
 
         Ok(AudioRecording {
@@ -229,6 +621,11 @@ impl RecorderState {
             channels,
             duration_seconds: duration,
             file_path,
+            first_sample_time_ms,
+            summary,
+            sample_rate_unreliable: self.rate_drift.load(Ordering::Relaxed),
+            paused_seconds,
+            device_error: self.device_error.get(),
         })
     }
 
@@ -265,6 +662,10 @@ impl RecorderState {
             let _ = handle.join();
         }
 
+        if let Some(mut level_task) = self.level_task.take() {
+            level_task.stop();
+        }
+
         // Finalize and drop the writer
         if let Some(writer) = self.writer.take() {
             if let Ok(mut w) = writer.lock() {
@@ -274,6 +675,9 @@ impl RecorderState {
 
         // Clear state
         self.file_path = None;
+        self.mode = RecordingMode::Manual;
+        self.paused_at = None;
+        self.paused_total = Duration::ZERO;
         self.sample_rate = 0;
         self.channels = 0;
 
@@ -281,9 +685,15 @@ impl RecorderState {
         Ok(())
     }
 
-    /// Get current recording ID if actively recording
+    /// State of the session's file writer, if a session is initialized
+    pub fn get_writer_status(&self) -> Option<WavWriterStatus> {
+        let writer = self.writer.as_ref()?.lock().ok()?;
+        Some(writer.get_status())
+    }
+
+    /// Get current recording ID if actively recording or paused
     pub fn get_current_recording_id(&self) -> Option<String> {
-        if self.is_recording.load(Ordering::Acquire) {
+        if self.is_recording.load(Ordering::Acquire) || self.paused_at.is_some() {
             self.file_path
                 .as_ref()
                 .and_then(|path| path.file_stem())
@@ -295,8 +705,220 @@ impl RecorderState {
     }
 }
 
+/// Moves a session's stream to the system default input device when it changes
+///
+/// Lives on the worker thread, which owns the stream. Switching only happens
+/// while not recording and before any audio has been written, because the WAV
+/// header is rewritten for the new device's format.
+struct DefaultDeviceFollower {
+    host_name: Option<String>,
+    current_device: String,
+    preferred_sample_rate: Option<u32>,
+    shared: StreamShared,
+    rate_drift: Arc<AtomicBool>,
+    on_rate_drift: Option<DriftCallback>,
+    on_switched: Option<DeviceSwitchCallback>,
+}
+
+impl DefaultDeviceFollower {
+    /// Rebuild the stream if the default device changed, returning the new stream
+    fn poll(&mut self) -> Option<Stream> {
+        if self.shared.is_recording.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let device = resolve_host(self.host_name.as_deref())
+            .ok()?
+            .default_input_device()?;
+        let device_name = device.name().ok()?;
+        if device_name == self.current_device {
+            return None;
+        }
+
+        info!(
+            "Default input device changed: {} -> {}",
+            self.current_device, device_name
+        );
+
+        match self.switch_to(&device) {
+            Ok(stream) => {
+                self.current_device = device_name.clone();
+                if let Some(on_switched) = &self.on_switched {
+                    on_switched(device_name);
+                }
+                Some(stream)
+            }
+            Err(e) => {
+                // Don't retry the same device every poll
+                warn!("Staying on {}: {}", self.current_device, e);
+                self.current_device = device_name;
+                None
+            }
+        }
+    }
+
+    /// Negotiate a config on `device`, reformat the WAV file and start a stream
+    fn switch_to(&self, device: &Device) -> Result<Stream> {
+        let config = get_optimal_config(device, self.preferred_sample_rate)?;
+        let sample_rate = config.sample_rate().0;
+        let channels = config.channels();
+
+        self.shared
+            .writer
+            .lock()
+            .map_err(|e| format!("Failed to lock writer: {}", e))?
+            .reset_format(sample_rate, channels)
+            .map_err(|e| format!("Failed to update WAV format: {}", e))?;
+
+        let stream_config = cpal::StreamConfig {
+            channels,
+            sample_rate: cpal::SampleRate(sample_rate),
+            buffer_size: cpal::BufferSize::Default,
+        };
+        let rate_monitor = RateMonitor::new(
+            sample_rate,
+            channels,
+            self.rate_drift.clone(),
+            self.on_rate_drift.clone(),
+        );
+
+        let stream = build_input_stream(
+            device,
+            &stream_config,
+            config.sample_format(),
+            self.shared.clone(),
+            rate_monitor,
+        )?;
+        stream
+            .play()
+            .map_err(|e| format!("Failed to start stream: {}", e))?;
+
+        info!(
+            "Audio stream moved to new default device: {} Hz, {} channels",
+            sample_rate, channels
+        );
+
+        Ok(stream)
+    }
+}
+
+/// Names of the audio host APIs (backends) available on this platform
+pub fn available_host_names() -> Vec<String> {
+    cpal::available_hosts()
+        .into_iter()
+        .map(|id| id.name().to_string())
+        .collect()
+}
+
+/// Get a host by name (case-insensitive), or the default host when `None`
+///
+/// Lets users pick a specific backend (e.g. ALSA vs JACK, WASAPI vs ASIO) when
+/// the one CPAL selects by default doesn't work for their setup.
+pub(crate) fn resolve_host(host_name: Option<&str>) -> Result<cpal::Host> {
+    let Some(host_name) = host_name else {
+        return Ok(cpal::default_host());
+    };
+
+    let host_id = cpal::available_hosts()
+        .into_iter()
+        .find(|id| id.name().eq_ignore_ascii_case(host_name))
+        .ok_or_else(|| {
+            format!(
+                "Audio host '{}' not available (available: {})",
+                host_name,
+                available_host_names().join(", ")
+            )
+        })?;
+
+    cpal::host_from_id(host_id).map_err(|e| format!("Failed to open audio host '{}': {}", host_name, e))
+}
+
+/// Rates listed for a device whose supported range covers them
+const COMMON_SAMPLE_RATES: [u32; 5] = [8000, 16000, 44100, 48000, 96000];
+
+/// Sample rates, channel counts and formats of every input device on a host
+///
+/// Config ranges can span thousands of rates, so a range is reported as the
+/// common rates inside it, or just its bounds if it contains none of them.
+pub fn enumerate_devices_with_caps(host_name: Option<&str>) -> Result<Vec<DeviceCapabilities>> {
+    let host = resolve_host(host_name)?;
+    let devices = host
+        .input_devices()
+        .map_err(|e| format!("Failed to get input devices: {}", e))?;
+
+    let mut capabilities = Vec::new();
+    for device in devices {
+        let Ok(name) = device.name() else {
+            continue;
+        };
+        let configs = match device.supported_input_configs() {
+            Ok(configs) => configs,
+            Err(e) => {
+                warn!("Failed to get configs for device '{}': {}", name, e);
+                continue;
+            }
+        };
+
+        let mut sample_rates = Vec::new();
+        let mut channels = Vec::new();
+        let mut formats = Vec::new();
+        for config in configs {
+            let (min_rate, max_rate) = (config.min_sample_rate().0, config.max_sample_rate().0);
+            let common: Vec<u32> = COMMON_SAMPLE_RATES
+                .into_iter()
+                .filter(|rate| (min_rate..=max_rate).contains(rate))
+                .collect();
+            if common.is_empty() {
+                sample_rates.extend([min_rate, max_rate]);
+            } else {
+                sample_rates.extend(common);
+            }
+            channels.push(config.channels());
+            formats.push(config.sample_format().to_string());
+        }
+        sample_rates.sort_unstable();
+        sample_rates.dedup();
+        channels.sort_unstable();
+        channels.dedup();
+        formats.sort();
+        formats.dedup();
+
+        let default_sample_rate = device
+            .default_input_config()
+            .map(|config| config.sample_rate().0)
+            .unwrap_or(0);
+
+        capabilities.push(DeviceCapabilities {
+            name,
+            supported_sample_rates: sample_rates,
+            supported_channels: channels,
+            supported_formats: formats,
+            default_sample_rate,
+        });
+    }
+
+    Ok(capabilities)
+}
+
+/// Run the same config negotiation as `init_session` without opening a stream
+pub fn preview_config(
+    device_name: &str,
+    preferred_sample_rate: Option<u32>,
+    host_name: Option<&str>,
+) -> Result<RecordingConfig> {
+    let host = resolve_host(host_name)?;
+    let device = find_device(&host, device_name)?;
+    let config = get_optimal_config(&device, preferred_sample_rate)?;
+
+    Ok(RecordingConfig {
+        sample_rate: config.sample_rate().0,
+        channels: config.channels(),
+        sample_format: config.sample_format().to_string(),
+    })
+}
+
 /// Find a recording device by name
-fn find_device(host: &cpal::Host, device_name: &str) -> Result<Device> {
+pub(crate) fn find_device(host: &cpal::Host, device_name: &str) -> Result<Device> {
     // Handle "default" device
     if device_name.to_lowercase() == "default" {
         return host
@@ -319,7 +941,7 @@ fn find_device(host: &cpal::Host, device_name: &str) -> Result<Device> {
 }
 
 /// Get optimal configuration for voice recording
-fn get_optimal_config(
+pub(crate) fn get_optimal_config(
     device: &Device,
     preferred_sample_rate: Option<u32>,
 ) -> Result<cpal::SupportedStreamConfig> {
@@ -409,63 +1031,163 @@ fn get_optimal_config(
     best_config.ok_or_else(|| "Failed to find suitable audio configuration".to_string())
 }
 
+/// State a session shares with the callbacks of its input stream
+///
+/// Cloned into each stream built for the session, including the replacements
+/// built by `DefaultDeviceFollower`, so they all write to the same file.
+#[derive(Clone)]
+struct StreamShared {
+    is_recording: Arc<AtomicBool>,
+    is_pushing: Arc<AtomicBool>,
+    writer: Arc<Mutex<WavWriter>>,
+    level_window: LevelWindow,
+    device_error: DeviceErrorSink,
+    noise_gate: SharedNoiseGate,
+    agc: SharedAgc,
+}
+
 /// Build input stream for any supported sample format
 fn build_input_stream(
     device: &Device,
     config: &cpal::StreamConfig,
     sample_format: SampleFormat,
-    is_recording: Arc<AtomicBool>,
-    writer: Arc<Mutex<WavWriter>>,
+    shared: StreamShared,
+    mut rate_monitor: RateMonitor,
 ) -> Result<Stream> {
-    let err_fn = |err| error!("Audio stream error: {}", err);
-
-    let stream = match sample_format {
-        SampleFormat::F32 => device
-            .build_input_stream(
-                config,
-                move |data: &[f32], _: &_| {
-                    if is_recording.load(Ordering::Relaxed) {
-                        if let Ok(mut w) = writer.lock() {
-                            let _ = w.write_samples_f32(data);
-                        }
-                    }
-                },
-                err_fn,
-                None,
-            )
-            .map_err(|e| format!("Failed to build F32 stream: {}", e))?,
-        SampleFormat::I16 => device
-            .build_input_stream(
-                config,
-                move |data: &[i16], _: &_| {
-                    if is_recording.load(Ordering::Relaxed) {
-                        if let Ok(mut w) = writer.lock() {
-                            let _ = w.write_samples_i16(data);
-                        }
-                    }
-                },
-                err_fn,
-                None,
-            )
-            .map_err(|e| format!("Failed to build I16 stream: {}", e))?,
-        SampleFormat::U16 => device
-            .build_input_stream(
-                config,
-                move |data: &[u16], _: &_| {
-                    if is_recording.load(Ordering::Relaxed) {
-                        if let Ok(mut w) = writer.lock() {
-                            let _ = w.write_samples_u16(data);
-                        }
-                    }
-                },
-                err_fn,
-                None,
-            )
-            .map_err(|e| format!("Failed to build U16 stream: {}", e))?,
-        _ => return Err(format!("Unsupported sample format: {:?}", sample_format)),
+    let StreamShared {
+        is_recording,
+        is_pushing,
+        writer,
+        level_window,
+        device_error,
+        noise_gate,
+        agc,
+    } = shared;
+    let on_error = move |err: cpal::StreamError| device_error.report(err);
+    let should_write =
+        move || is_recording.load(Ordering::Relaxed) && is_pushing.load(Ordering::Relaxed);
+    let sink = SampleSink {
+        writer,
+        noise_gate,
+        agc,
+        sample_rate: config.sample_rate.0,
+        channels: config.channels,
     };
+    match sample_format {
+        SampleFormat::F32 => build_typed_stream_with_errors(
+            device,
+            config,
+            move |data: &[f32]| {
+                rate_monitor.observe(data.len());
+                push_samples(&level_window, data.iter().copied());
+                if should_write() {
+                    sink.process_samples(data, |s| s, WavWriter::write_samples_f32);
+                }
+            },
+            on_error,
+        ),
+        SampleFormat::I16 => build_typed_stream_with_errors(
+            device,
+            config,
+            move |data: &[i16]| {
+                rate_monitor.observe(data.len());
+                push_samples(&level_window, data.iter().map(|&s| f32::from_sample(s)));
+                if should_write() {
+                    sink.process_samples(data, i16_to_f32, WavWriter::write_samples_i16);
+                }
+            },
+            on_error,
+        ),
+        SampleFormat::U16 => build_typed_stream_with_errors(
+            device,
+            config,
+            move |data: &[u16]| {
+                rate_monitor.observe(data.len());
+                push_samples(&level_window, data.iter().map(|&s| f32::from_sample(s)));
+                if should_write() {
+                    sink.process_samples(data, u16_to_f32, WavWriter::write_samples_u16);
+                }
+            },
+            on_error,
+        ),
+        _ => Err(format!("Unsupported sample format: {:?}", sample_format)),
+    }
+}
+
+/// Where a stream callback writes captured audio, processing it on the way
+struct SampleSink {
+    writer: Arc<Mutex<WavWriter>>,
+    noise_gate: SharedNoiseGate,
+    agc: SharedAgc,
+    sample_rate: u32,
+    channels: u16,
+}
+
+impl SampleSink {
+    /// Write a buffer, passing it through the noise gate and then AGC when set
+    ///
+    /// With neither, the samples go to `write` unchanged; otherwise they are
+    /// converted by `to_f32` (the writer's own conversion), processed and
+    /// written as f32. Gating first keeps AGC from boosting background noise.
+    fn process_samples<T: Copy>(
+        &self,
+        data: &[T],
+        to_f32: fn(T) -> f32,
+        write: fn(&mut WavWriter, &[T]) -> io::Result<()>,
+    ) {
+        let Ok(mut w) = self.writer.lock() else {
+            return;
+        };
+        let (Ok(mut noise_gate), Ok(mut agc)) = (self.noise_gate.lock(), self.agc.lock()) else {
+            return;
+        };
+        if noise_gate.is_none() && agc.is_none() {
+            let _ = write(&mut w, data);
+            return;
+        }
 
-    Ok(stream)
+        let mut samples: Vec<f32> = data.iter().map(|&s| to_f32(s)).collect();
+        if let Some(gate) = noise_gate.as_mut() {
+            gate.process(&mut samples, self.sample_rate, self.channels);
+        }
+        if let Some(agc) = agc.as_mut() {
+            agc.process(&mut samples, self.sample_rate, self.channels);
+        }
+        let _ = w.write_samples_f32(&samples);
+    }
+}
+
+/// Build an input stream that hands every buffer of `T` samples to `on_data`
+///
+/// Stream errors are only logged.
+pub(crate) fn build_typed_stream<T, F>(
+    device: &Device,
+    config: &cpal::StreamConfig,
+    on_data: F,
+) -> Result<Stream>
+where
+    T: SizedSample,
+    F: FnMut(&[T]) + Send + 'static,
+{
+    let err_fn = |err| error!("Audio stream error: {}", err);
+    build_typed_stream_with_errors(device, config, on_data, err_fn)
+}
+
+/// `build_typed_stream`, handing stream errors to `on_error`
+fn build_typed_stream_with_errors<T, F, E>(
+    device: &Device,
+    config: &cpal::StreamConfig,
+    mut on_data: F,
+    on_error: E,
+) -> Result<Stream>
+where
+    T: SizedSample,
+    F: FnMut(&[T]) + Send + 'static,
+    E: FnMut(cpal::StreamError) + Send + 'static,
+{
+    device
+        .build_input_stream(config, move |data: &[T], _: &_| on_data(data), on_error, None)
+        .map_err(|e| format!("Failed to build {:?} stream: {}", T::FORMAT, e))
 }
 
 impl Drop for RecorderState {
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// Size of the header written by `WavWriter`: the canonical 44 bytes plus the
+/// 36-byte chunk reserved for RF64 promotion
+const WAV_HEADER_BYTES: u64 = 80;
+
+/// Typical FLAC compression ratio for speech relative to 16-bit PCM
+const FLAC_COMPRESSION_RATIO: f64 = 0.55;
+
+/// Typical Opus bitrate for voice, per channel (bits per second)
+const OPUS_VOICE_BITRATE_PER_CHANNEL: u64 = 32_000;
+
+/// Recording formats that storage can be estimated for
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum StorageFormat {
+    /// 32-bit IEEE float WAV (what `WavWriter` produces by default)
+    WavFloat,
+    /// 16-bit integer PCM WAV
+    WavPcm16,
+    /// 24-bit integer PCM WAV
+    WavPcm24,
+    /// FLAC, estimated from a typical speech compression ratio
+    Flac,
+    /// Opus, estimated from a typical voice bitrate
+    Opus,
+}
+
+/// Projected storage usage for a recording - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageEstimate {
+    pub bytes: u64,
+    pub human_readable: String,
+    /// False for compressed formats where the size is only a typical ratio
+    pub exact: bool,
+}
+
+/// Estimate the file size of a recording with the given format parameters
+///
+/// WAV sizes are exact (header + raw sample data). FLAC and Opus sizes are
+/// approximations since the real size depends on the audio content.
+pub fn estimate_storage_bytes(
+    duration_seconds: f64,
+    format: StorageFormat,
+    sample_rate: u32,
+    channels: u16,
+) -> StorageEstimate {
+    let duration_seconds = duration_seconds.max(0.0);
+    let frames = (duration_seconds * sample_rate as f64).round() as u64;
+    let samples = frames * channels as u64;
+
+    let (bytes, exact) = match format {
+        StorageFormat::WavFloat => (WAV_HEADER_BYTES + samples * 4, true),
+        StorageFormat::WavPcm16 => (WAV_HEADER_BYTES + samples * 2, true),
+        StorageFormat::WavPcm24 => (WAV_HEADER_BYTES + samples * 3, true),
+        StorageFormat::Flac => (((samples * 2) as f64 * FLAC_COMPRESSION_RATIO) as u64, false),
+        StorageFormat::Opus => {
+            // Opus always encodes internally at 48kHz, so the input rate doesn't matter
+            let bitrate = OPUS_VOICE_BITRATE_PER_CHANNEL * channels as u64;
+            ((bitrate as f64 / 8.0 * duration_seconds) as u64, false)
+        }
+    };
+
+    StorageEstimate {
+        bytes,
+        human_readable: format_bytes(bytes),
+        exact,
+    }
+}
+
+/// Format a byte count as a human-readable string (e.g. "1.5 GB")
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
@@ -0,0 +1,101 @@
+use crate::audio_ops::{rms_db, FRAME_MS};
+use serde::Serialize;
+
+/// Frames quieter than this (RMS, dBFS) count towards the silence ratio
+const SILENCE_THRESHOLD_DB: f32 = -50.0;
+
+/// Samples at or above this magnitude are counted as clipped
+const CLIP_THRESHOLD: f32 = 0.999;
+
+/// Quality overview of a finished recording - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingSummary {
+    /// Highest sample magnitude, in dBFS
+    pub peak_db: f32,
+    /// RMS level over the whole recording, in dBFS
+    pub average_rms_db: f32,
+    pub clipped_samples: u64,
+    /// Fraction (0.0..=1.0) of 10 ms frames below the silence threshold
+    pub silence_ratio: f32,
+}
+
+/// Builds a `RecordingSummary` as samples are written, so stopping a recording
+/// doesn't need to re-read the file
+pub struct SummaryAccumulator {
+    frame_len: usize,
+    peak: f32,
+    sum_squares: f64,
+    samples: u64,
+    clipped: u64,
+    frame_sum_squares: f32,
+    frame_count: usize,
+    frames: u64,
+    silent_frames: u64,
+}
+
+impl SummaryAccumulator {
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        let frame_len = (sample_rate * FRAME_MS / 1000) as usize * channels as usize;
+        Self {
+            frame_len: frame_len.max(1),
+            peak: 0.0,
+            sum_squares: 0.0,
+            samples: 0,
+            clipped: 0,
+            frame_sum_squares: 0.0,
+            frame_count: 0,
+            frames: 0,
+            silent_frames: 0,
+        }
+    }
+
+    /// Add one (interleaved) sample normalized to [-1.0, 1.0]
+    pub fn push(&mut self, sample: f32) {
+        let magnitude = sample.abs();
+        self.peak = self.peak.max(magnitude);
+        if magnitude >= CLIP_THRESHOLD {
+            self.clipped += 1;
+        }
+
+        let square = sample * sample;
+        self.sum_squares += square as f64;
+        self.samples += 1;
+
+        self.frame_sum_squares += square;
+        self.frame_count += 1;
+        if self.frame_count == self.frame_len {
+            self.close_frame();
+        }
+    }
+
+    fn close_frame(&mut self) {
+        if rms_db(self.frame_sum_squares / self.frame_count as f32) < SILENCE_THRESHOLD_DB {
+            self.silent_frames += 1;
+        }
+        self.frames += 1;
+        self.frame_sum_squares = 0.0;
+        self.frame_count = 0;
+    }
+
+    /// Summary of everything pushed so far (a trailing partial frame is ignored)
+    pub fn summary(&self) -> RecordingSummary {
+        let mean_square = if self.samples == 0 {
+            0.0
+        } else {
+            (self.sum_squares / self.samples as f64) as f32
+        };
+        let silence_ratio = if self.frames == 0 {
+            0.0
+        } else {
+            self.silent_frames as f32 / self.frames as f32
+        };
+
+        RecordingSummary {
+            peak_db: rms_db(self.peak * self.peak),
+            average_rms_db: rms_db(mean_square),
+            clipped_samples: self.clipped,
+            silence_ratio,
+        }
+    }
+}
@@ -1,32 +1,325 @@
+use crate::recorder::summary::{RecordingSummary, SummaryAccumulator};
+use serde::{Deserialize, Serialize};
 use std::fs::File;
-use std::io::{self, BufWriter, Seek, SeekFrom, Write};
-use std::path::PathBuf;
-use std::time::Instant;
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tracing::{debug, info};
 
+/// Size of the fixed part of a `bext` chunk (EBU Tech 3285, version 1)
+const BEXT_FIXED_SIZE: usize = 602;
+
+/// Payload size of a `ds64` chunk without a table (EBU Tech 3306): 64-bit
+/// RIFF size, data size and sample count plus a 32-bit table length
+const DS64_PAYLOAD_SIZE: u32 = 28;
+
+/// Bytes between the RIFF size field and the first data byte: `WAVE`, the
+/// reserved `JUNK`/`ds64` chunk, the fmt chunk and the data chunk header
+const HEADER_SIZE_AFTER_RIFF: u64 = 4 + (8 + DS64_PAYLOAD_SIZE as u64) + (8 + 16) + 8;
+
+/// Offset of the first data byte: the RIFF marker and size, then the rest of the header
+const DATA_START: u64 = 8 + HEADER_SIZE_AFTER_RIFF;
+
+/// Header fields of a recording found on disk by `WavWriter::recover`
+#[derive(Debug, Clone, Copy)]
+pub struct RecoveredWav {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub duration_seconds: f32,
+    /// The header sizes were wrong and have been rewritten
+    pub repaired: bool,
+}
+
+/// Broadcast Wave (BWF) metadata, written as a `bext` chunk when the file is finalized
+///
+/// Origination date/time are recorded in UTC. They start out as the moment the
+/// metadata is created and are moved to the capture time of the first sample
+/// once audio arrives, so the time reference can be used to align tracks
+/// recorded on separate devices.
+#[derive(Debug, Clone)]
+pub struct BextMetadata {
+    pub description: String,
+    pub originator: String,
+    pub originator_reference: String,
+    pub origination: SystemTime,
+}
+
+impl BextMetadata {
+    pub fn new(description: String) -> Self {
+        Self {
+            description,
+            originator: "Whispering".to_string(),
+            originator_reference: String::new(),
+            origination: SystemTime::now(),
+        }
+    }
+
+    /// Serialize the chunk (header included, padded to an even length)
+    fn to_chunk(&self, sample_rate: u32, channels: u16, bits_per_sample: u16) -> Vec<u8> {
+        let since_epoch = self
+            .origination
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let secs = since_epoch.as_secs();
+        let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+        let secs_of_day = secs % 86_400;
+        let date = format!("{:04}-{:02}-{:02}", year, month, day);
+        let time = format!(
+            "{:02}:{:02}:{:02}",
+            secs_of_day / 3600,
+            (secs_of_day / 60) % 60,
+            secs_of_day % 60
+        );
+        // Time reference is the sample count since midnight, kept sub-second
+        // accurate so tracks from different devices line up
+        let time_reference = secs_of_day * sample_rate as u64
+            + since_epoch.subsec_nanos() as u64 * sample_rate as u64 / 1_000_000_000;
+
+        let mode = if channels == 1 { "mono" } else { "stereo" };
+        let coding_history = format!(
+            "A=PCM,F={},W={},M={},T=Whispering\r\n",
+            sample_rate, bits_per_sample, mode
+        );
+
+        let mut payload = Vec::with_capacity(BEXT_FIXED_SIZE + coding_history.len());
+        write_fixed_ascii(&mut payload, &self.description, 256);
+        write_fixed_ascii(&mut payload, &self.originator, 32);
+        write_fixed_ascii(&mut payload, &self.originator_reference, 32);
+        write_fixed_ascii(&mut payload, &date, 10);
+        write_fixed_ascii(&mut payload, &time, 8);
+        payload.extend_from_slice(&(time_reference as u32).to_le_bytes()); // TimeReferenceLow
+        payload.extend_from_slice(&((time_reference >> 32) as u32).to_le_bytes()); // TimeReferenceHigh
+        payload.extend_from_slice(&1u16.to_le_bytes()); // Version
+        payload.resize(payload.len() + 64, 0); // UMID (unused)
+        payload.resize(payload.len() + 10, 0); // Loudness fields (unused)
+        payload.resize(BEXT_FIXED_SIZE, 0); // Reserved
+        payload.extend_from_slice(coding_history.as_bytes());
+
+        let mut chunk = Vec::with_capacity(8 + payload.len() + 1);
+        chunk.extend_from_slice(b"bext");
+        chunk.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        chunk.extend_from_slice(&payload);
+        if payload.len() % 2 == 1 {
+            chunk.push(0); // RIFF chunks are word-aligned
+        }
+        chunk
+    }
+}
+
+/// Write an ASCII string into a fixed-width, NUL-padded field
+fn write_fixed_ascii(buf: &mut Vec<u8>, value: &str, width: usize) {
+    let bytes: Vec<u8> = value.bytes().filter(|b| b.is_ascii()).take(width).collect();
+    buf.extend_from_slice(&bytes);
+    buf.resize(buf.len() + (width - bytes.len()), 0);
+}
+
+/// Convert days since the Unix epoch to a (year, month, day) civil date
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's days_from_civil inverse
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = (if mp < 10 { mp + 3 } else { mp - 9 }) as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Sample encoding of the data chunk
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PcmFormat {
+    /// 32-bit IEEE float, lossless for the captured samples
+    #[default]
+    Float32,
+    /// 16-bit integer PCM, half the size of float
+    Int16,
+    /// 24-bit integer PCM, preferred by many DAWs
+    Int24,
+}
+
+impl PcmFormat {
+    /// `AudioFormat` field of the fmt chunk: 3 for IEEE float, 1 for integer PCM
+    fn format_tag(self) -> u16 {
+        match self {
+            PcmFormat::Float32 => 3,
+            PcmFormat::Int16 | PcmFormat::Int24 => 1,
+        }
+    }
+
+    fn bits_per_sample(self) -> u16 {
+        match self {
+            PcmFormat::Float32 => 32,
+            PcmFormat::Int16 => 16,
+            PcmFormat::Int24 => 24,
+        }
+    }
+
+    fn from_header(format_tag: u16, bits_per_sample: u16) -> Option<Self> {
+        match (format_tag, bits_per_sample) {
+            (3, 32) => Some(PcmFormat::Float32),
+            (1, 16) => Some(PcmFormat::Int16),
+            (1, 24) => Some(PcmFormat::Int24),
+            _ => None,
+        }
+    }
+
+    /// Append `sample` as little-endian bytes, clamping integer formats to full scale
+    fn encode(self, sample: f32, out: &mut Vec<u8>) {
+        match self {
+            PcmFormat::Float32 => out.extend_from_slice(&sample.to_le_bytes()),
+            PcmFormat::Int16 => {
+                let value = (sample.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16;
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            PcmFormat::Int24 => {
+                let value = (sample.clamp(-1.0, 1.0) * INT24_MAX).round() as i32;
+                out.extend_from_slice(&value.to_le_bytes()[..3]);
+            }
+        }
+    }
+
+    /// Read one sample of this format from the start of `bytes`
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            PcmFormat::Float32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            PcmFormat::Int16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / i16::MAX as f32,
+            PcmFormat::Int24 => {
+                // Sign-extend by placing the 24 bits at the top of an i32
+                let value = i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8;
+                value as f32 / INT24_MAX
+            }
+        }
+    }
+}
+
+/// Largest 24-bit sample value
+const INT24_MAX: f32 = 8_388_607.0;
+
+/// How often buffered audio is pushed to the OS unless configured otherwise
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Options for a new `WavWriter`
+#[derive(Debug, Clone, Default)]
+pub struct WavWriterConfig {
+    pub format: PcmFormat,
+    /// Written as a BWF `bext` chunk after the data chunk on finalize
+    pub bext: Option<BextMetadata>,
+    /// See `WavWriter::set_header_update_bytes`
+    pub header_update_bytes: Option<u64>,
+    /// See `WavWriter::enable_dc_offset_removal`
+    pub remove_dc_offset: bool,
+    /// See `WavWriter::set_flush_interval_ms`; `None` keeps the 1 second default
+    pub flush_interval_ms: Option<u64>,
+    /// See `WavWriter::set_sync_to_disk`
+    pub sync_to_disk: bool,
+    /// File size past which the file is promoted to RF64. `None`, or anything
+    /// above it, keeps the 4 GB limit of the 32-bit RIFF sizes
+    pub rf64_threshold_bytes: Option<u64>,
+}
+
+/// Pole of the DC-blocking filter; puts the cutoff below 10 Hz at common rates
+const DC_FILTER_POLE: f32 = 0.999;
+
+/// Single-pole high-pass filter that removes a constant offset from each
+/// channel: `y[n] = x[n] - x[n-1] + 0.999 * y[n-1]`
+struct DcBlocker {
+    prev_input: Vec<f32>,
+    prev_output: Vec<f32>,
+}
+
+impl DcBlocker {
+    fn new(channels: u16) -> Self {
+        Self {
+            prev_input: vec![0.0; channels as usize],
+            prev_output: vec![0.0; channels as usize],
+        }
+    }
+
+    fn process(&mut self, channel: usize, sample: f32) -> f32 {
+        let output = sample - self.prev_input[channel] + DC_FILTER_POLE * self.prev_output[channel];
+        self.prev_input[channel] = sample;
+        self.prev_output[channel] = output;
+        output
+    }
+}
+
+/// Snapshot of an open writer, for diagnostics - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WavWriterStatus {
+    pub file_path: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub format: PcmFormat,
+    pub samples_written: u64,
+    pub duration_seconds: f32,
+    pub rf64: bool,
+    pub dc_offset_removal: bool,
+    pub sync_to_disk: bool,
+    pub flush_interval_ms: u64,
+}
+
 /// WAV file writer that supports progressive writing with header updates
 pub struct WavWriter {
     writer: BufWriter<File>,
     sample_rate: u32,
     channels: u16,
-    #[allow(dead_code)]
+    format: PcmFormat,
     bits_per_sample: u16,
     bytes_per_sample: u16,
     data_chunk_size_pos: u64,
     riff_chunk_size_pos: u64,
+    /// Start of the reserved chunk that becomes `ds64` when promoted to RF64
+    ds64_chunk_pos: u64,
+    /// Start of the fmt chunk's payload
+    fmt_pos: u64,
+    /// The file has outgrown 32-bit sizes and is written as RF64
+    rf64: bool,
+    /// File size past which the file is promoted to RF64
+    rf64_threshold: u64,
     samples_written: u64,
     last_header_update: Instant,
+    /// Samples written as of the last header update
+    samples_at_header_update: u64,
+    /// Also update headers after this many data bytes, regardless of time
+    header_update_bytes: Option<u64>,
+    flush_interval: Duration,
+    last_flush: Instant,
+    /// fsync the data after every flush
+    sync_to_disk: bool,
     file_path: PathBuf,
+    bext: Option<BextMetadata>,
+    first_sample_time: Option<SystemTime>,
+    summary: SummaryAccumulator,
+    /// Set once DC offset removal is enabled; off by default
+    dc_blocker: Option<DcBlocker>,
+    /// Encoded bytes of the buffer being written
+    encode_buffer: Vec<u8>,
 }
 
 impl WavWriter {
     /// Create a new WAV file and write initial headers
-    pub fn new(file_path: PathBuf, sample_rate: u32, channels: u16) -> io::Result<Self> {
+    ///
+    /// When `config.bext` is set, a BWF `bext` chunk is written after the data
+    /// chunk each time the file is finalized. A `JUNK` chunk is reserved after
+    /// the RIFF header so the file can be promoted to RF64 in place if it grows
+    /// past 4 GB (about 3.5 hours of 48 kHz stereo); readers skip it otherwise.
+    /// Samples are stored as `config.format`, 32-bit float unless set.
+    pub fn new(
+        file_path: PathBuf,
+        sample_rate: u32,
+        channels: u16,
+        config: WavWriterConfig,
+    ) -> io::Result<Self> {
         let file = File::create(&file_path)?;
         let mut writer = BufWriter::new(file);
 
-        // We'll use 32-bit float format for consistency with the current implementation
-        let bits_per_sample = 32;
+        let format = config.format;
+        let bits_per_sample = format.bits_per_sample();
         let bytes_per_sample = bits_per_sample / 8;
 
         // Write initial WAV header with placeholder sizes
@@ -38,10 +331,17 @@ impl WavWriter {
         writer.write_all(&[0xFF, 0xFF, 0xFF, 0xFF])?; // Placeholder for file size - 8
         writer.write_all(b"WAVE")?;
 
+        // Space for a ds64 chunk, used only if the file outgrows 32-bit sizes
+        let ds64_chunk_pos = writer.stream_position()?;
+        writer.write_all(b"JUNK")?;
+        writer.write_all(&DS64_PAYLOAD_SIZE.to_le_bytes())?;
+        writer.write_all(&[0u8; DS64_PAYLOAD_SIZE as usize])?;
+
         // fmt chunk
         writer.write_all(b"fmt ")?;
         writer.write_all(&16u32.to_le_bytes())?; // Subchunk1Size (16 for PCM)
-        writer.write_all(&3u16.to_le_bytes())?; // AudioFormat (3 for IEEE Float)
+        let fmt_pos = writer.stream_position()?;
+        writer.write_all(&format.format_tag().to_le_bytes())?; // AudioFormat (3 float, 1 PCM)
         writer.write_all(&channels.to_le_bytes())?;
         writer.write_all(&sample_rate.to_le_bytes())?;
         let byte_rate = sample_rate * channels as u32 * bytes_per_sample as u32;
@@ -58,101 +358,359 @@ impl WavWriter {
         writer.flush()?;
 
         info!(
-            "Created WAV file at {:?}: {}Hz, {} channels, {}-bit float",
-            file_path, sample_rate, channels, bits_per_sample
+            "Created WAV file at {:?}: {}Hz, {} channels, {:?}",
+            file_path, sample_rate, channels, format
         );
 
         Ok(Self {
             writer,
             sample_rate,
             channels,
+            format,
             bits_per_sample,
             bytes_per_sample,
             data_chunk_size_pos,
             riff_chunk_size_pos,
+            ds64_chunk_pos,
+            fmt_pos,
+            rf64: false,
+            rf64_threshold: config
+                .rf64_threshold_bytes
+                .map_or(u32::MAX as u64, |bytes| bytes.min(u32::MAX as u64)),
             samples_written: 0,
             last_header_update: Instant::now(),
+            samples_at_header_update: 0,
+            header_update_bytes: config.header_update_bytes.filter(|&b| b > 0),
+            flush_interval: config
+                .flush_interval_ms
+                .map_or(DEFAULT_FLUSH_INTERVAL, Duration::from_millis),
+            last_flush: Instant::now(),
+            sync_to_disk: config.sync_to_disk,
             file_path,
+            bext: config.bext,
+            first_sample_time: None,
+            summary: SummaryAccumulator::new(sample_rate, channels),
+            dc_blocker: config.remove_dc_offset.then(|| DcBlocker::new(channels)),
+            encode_buffer: Vec::new(),
+        })
+    }
+
+    /// Fix the header sizes of a recording left behind by a crash
+    ///
+    /// Only files written by `WavWriter` are accepted, since its header layout
+    /// is fixed. Headers are refreshed about once a second while recording,
+    /// so after a crash they hold placeholder or stale sizes that hide the
+    /// latest audio. Every whole frame on disk after the data chunk header is
+    /// counted as audio and the sizes (RIFF or RF64) are rewritten. Files
+    /// whose sizes already match, including finalized ones followed by a
+    /// `bext` chunk, are left untouched.
+    pub fn recover(file_path: PathBuf) -> io::Result<RecoveredWav> {
+        let mut file = File::options().read(true).write(true).open(&file_path)?;
+        let RecordingHeader {
+            rf64,
+            format,
+            sample_rate,
+            channels,
+            declared_data_size,
+        } = read_recording_header(&mut file)?;
+        let bits_per_sample = format.bits_per_sample();
+        let bytes_per_sample = bits_per_sample / 8;
+        let block_align = channels as u64 * bytes_per_sample as u64;
+
+        let bytes_on_disk = file.metadata()?.len() - DATA_START;
+
+        let duration_of = |data_size: u64| {
+            (data_size / bytes_per_sample as u64) as f32 / (sample_rate as f32 * channels as f32)
+        };
+        if let Some(data_size) = declared_data_size {
+            let valid = data_size == bytes_on_disk
+                || (data_size < bytes_on_disk && {
+                    // A finalized file can be followed by its bext chunk, after
+                    // a pad byte if the data size is odd
+                    let mut chunk_id = [0u8; 4];
+                    file.seek(SeekFrom::Start(DATA_START + data_size + data_size % 2))?;
+                    file.read_exact(&mut chunk_id).is_ok() && &chunk_id == b"bext"
+                });
+            if valid {
+                info!("WAV file {:?} has valid headers, leaving it untouched", file_path);
+                return Ok(RecoveredWav {
+                    sample_rate,
+                    channels,
+                    duration_seconds: duration_of(data_size),
+                    repaired: false,
+                });
+            }
+        }
+
+        // Drop a frame cut short by the crash
+        let data_size = bytes_on_disk / block_align * block_align;
+        file.set_len(DATA_START + data_size)?;
+        file.seek(SeekFrom::End(0))?;
+
+        let mut writer = Self {
+            writer: BufWriter::new(file),
+            sample_rate,
+            channels,
+            format,
+            bits_per_sample,
+            bytes_per_sample,
+            data_chunk_size_pos: 76,
+            riff_chunk_size_pos: 4,
+            ds64_chunk_pos: 12,
+            fmt_pos: 56,
+            rf64,
+            rf64_threshold: u32::MAX as u64,
+            samples_written: data_size / bytes_per_sample as u64,
+            last_header_update: Instant::now(),
+            samples_at_header_update: 0,
+            header_update_bytes: None,
+            flush_interval: DEFAULT_FLUSH_INTERVAL,
+            last_flush: Instant::now(),
+            sync_to_disk: false,
+            file_path,
+            bext: None,
+            first_sample_time: None,
+            summary: SummaryAccumulator::new(sample_rate, channels),
+            dc_blocker: None,
+            encode_buffer: Vec::new(),
+        };
+        writer.finalize()?;
+
+        Ok(RecoveredWav {
+            sample_rate,
+            channels,
+            duration_seconds: writer.get_duration_seconds(),
+            repaired: true,
         })
     }
 
     /// Write f32 samples to the WAV file
     pub fn write_samples_f32(&mut self, samples: &[f32]) -> io::Result<()> {
-        // Write samples as little-endian f32
-        for sample in samples {
-            self.writer.write_all(&sample.to_le_bytes())?;
+        self.mark_first_sample(samples.len());
+
+        // Write samples in the file's format
+        for (index, &sample) in samples.iter().enumerate() {
+            self.write_sample(index, sample)?;
         }
 
         self.samples_written += samples.len() as u64;
 
-        // Update headers periodically (every second)
-        if self.last_header_update.elapsed().as_secs() >= 1 {
-            self.update_headers()?;
-            self.last_header_update = Instant::now();
-        }
+        // Update headers periodically (every second, or every N bytes)
+        self.maybe_update_headers()?;
 
         Ok(())
     }
 
     /// Write i16 samples to the WAV file (converting to f32)
     pub fn write_samples_i16(&mut self, samples: &[i16]) -> io::Result<()> {
+        self.mark_first_sample(samples.len());
+
         // Convert i16 to f32 and write
-        for &sample in samples {
-            let f32_sample = sample as f32 / i16::MAX as f32;
-            self.writer.write_all(&f32_sample.to_le_bytes())?;
+        for (index, &sample) in samples.iter().enumerate() {
+            self.write_sample(index, i16_to_f32(sample))?;
         }
 
         self.samples_written += samples.len() as u64;
 
         // Update headers periodically
-        if self.last_header_update.elapsed().as_secs() >= 1 {
-            self.update_headers()?;
-            self.last_header_update = Instant::now();
-        }
+        self.maybe_update_headers()?;
 
         Ok(())
     }
 
     /// Write u16 samples to the WAV file (converting to f32)
     pub fn write_samples_u16(&mut self, samples: &[u16]) -> io::Result<()> {
+        self.mark_first_sample(samples.len());
+
         // Convert u16 to f32 and write
-        for &sample in samples {
-            let f32_sample = (sample as f32 / u16::MAX as f32) * 2.0 - 1.0;
-            self.writer.write_all(&f32_sample.to_le_bytes())?;
+        for (index, &sample) in samples.iter().enumerate() {
+            self.write_sample(index, u16_to_f32(sample))?;
         }
 
         self.samples_written += samples.len() as u64;
 
         // Update headers periodically
-        if self.last_header_update.elapsed().as_secs() >= 1 {
+        self.maybe_update_headers()?;
+
+        Ok(())
+    }
+
+    /// Write the sample at `index` in the current buffer, filtering it first
+    /// when DC offset removal is enabled
+    fn write_sample(&mut self, index: usize, sample: f32) -> io::Result<()> {
+        let sample = match &mut self.dc_blocker {
+            Some(dc_blocker) => {
+                let channel = (self.samples_written + index as u64) % self.channels as u64;
+                dc_blocker.process(channel as usize, sample)
+            }
+            None => sample,
+        };
+        self.encode_buffer.clear();
+        self.format.encode(sample, &mut self.encode_buffer);
+        self.writer.write_all(&self.encode_buffer)?;
+        self.summary.push(sample);
+        Ok(())
+    }
+
+    /// Remove any constant DC offset from the samples written from now on
+    ///
+    /// Some microphones shift the whole waveform away from zero, costing
+    /// headroom. A high-pass filter with a cutoff of a few Hz takes the offset
+    /// out without touching speech. Off by default.
+    pub fn enable_dc_offset_removal(&mut self) {
+        if self.dc_blocker.is_none() {
+            self.dc_blocker = Some(DcBlocker::new(self.channels));
+        }
+    }
+
+    /// Rewrite the fmt chunk for a new stream format before any audio is written
+    ///
+    /// Used when the session's stream is rebuilt on a different device. Fails
+    /// once samples have been written, since they'd be reinterpreted.
+    pub fn reset_format(&mut self, sample_rate: u32, channels: u16) -> io::Result<()> {
+        if self.samples_written > 0 {
+            return Err(io::Error::other(
+                "Cannot change format after samples have been written",
+            ));
+        }
+
+        let current_pos = self.writer.stream_position()?;
+
+        // Channels, sample rate, byte rate and block align follow the format tag
+        let byte_rate = sample_rate * channels as u32 * self.bytes_per_sample as u32;
+        let block_align = channels * self.bytes_per_sample;
+        self.writer.seek(SeekFrom::Start(self.fmt_pos + 2))?;
+        self.writer.write_all(&channels.to_le_bytes())?;
+        self.writer.write_all(&sample_rate.to_le_bytes())?;
+        self.writer.write_all(&byte_rate.to_le_bytes())?;
+        self.writer.write_all(&block_align.to_le_bytes())?;
+
+        self.writer.seek(SeekFrom::Start(current_pos))?;
+        self.writer.flush()?;
+
+        self.sample_rate = sample_rate;
+        self.channels = channels;
+        self.summary = SummaryAccumulator::new(sample_rate, channels);
+        if self.dc_blocker.is_some() {
+            self.dc_blocker = Some(DcBlocker::new(channels));
+        }
+
+        info!(
+            "Changed WAV format of {:?}: {}Hz, {} channels",
+            self.file_path, sample_rate, channels
+        );
+
+        Ok(())
+    }
+
+    /// Update headers every `bytes` of sample data, in addition to once a second
+    ///
+    /// Headers are only rewritten (and the buffer flushed) on update, so this
+    /// bounds how much audio a crash can leave unreadable independently of the
+    /// sample rate. `None` keeps the time-based trigger only.
+    pub fn set_header_update_bytes(&mut self, bytes: Option<u64>) {
+        self.header_update_bytes = bytes.filter(|&b| b > 0);
+    }
+
+    /// Push buffered audio to the OS at least every `ms` milliseconds
+    ///
+    /// Header updates flush too, so this only matters below the once-a-second
+    /// header refresh; shorter intervals lose less audio to a crash at the cost
+    /// of more write calls.
+    pub fn set_flush_interval_ms(&mut self, ms: u64) {
+        self.flush_interval = Duration::from_millis(ms);
+    }
+
+    /// fsync the file's data (not its metadata) after every flush
+    ///
+    /// A flush only reaches the OS cache, which a power cut still loses. With
+    /// this enabled long recordings survive that too, at the cost of a disk
+    /// sync every flush interval.
+    pub fn set_sync_to_disk(&mut self, enabled: bool) {
+        self.sync_to_disk = enabled;
+    }
+
+    /// Update headers if a second has passed or enough bytes have been written,
+    /// and flush if the flush interval has passed
+    fn maybe_update_headers(&mut self) -> io::Result<()> {
+        let unflushed_bytes =
+            (self.samples_written - self.samples_at_header_update) * self.bytes_per_sample as u64;
+        let bytes_due = self
+            .header_update_bytes
+            .is_some_and(|limit| unflushed_bytes >= limit);
+
+        if bytes_due || self.last_header_update.elapsed().as_secs() >= 1 {
             self.update_headers()?;
             self.last_header_update = Instant::now();
+            self.samples_at_header_update = self.samples_written;
+        } else if self.last_flush.elapsed() >= self.flush_interval {
+            self.flush()?;
         }
 
         Ok(())
     }
 
+    /// Record when the first non-empty buffer arrived
+    ///
+    /// Writes happen directly from the audio callback, so this is as close to
+    /// the actual capture time as we can get without a stream clock.
+    fn mark_first_sample(&mut self, len: usize) {
+        if len == 0 || self.first_sample_time.is_some() {
+            return;
+        }
+
+        let now = SystemTime::now();
+        self.first_sample_time = Some(now);
+        if let Some(bext) = &mut self.bext {
+            bext.origination = now;
+        }
+    }
+
     /// Update the WAV header size fields
     fn update_headers(&mut self) -> io::Result<()> {
+        self.update_headers_with_trailer(0)
+    }
+
+    /// Update the WAV header size fields, accounting for chunks written after the data
+    fn update_headers_with_trailer(&mut self, trailer_size: u64) -> io::Result<()> {
         let current_pos = self.writer.stream_position()?;
 
         // Calculate sizes
         let data_size = self.samples_written * self.bytes_per_sample as u64;
-        let file_size = 36 + data_size; // 36 = header size minus RIFF header
+        let file_size = HEADER_SIZE_AFTER_RIFF + data_size + trailer_size;
+
+        // Past 4 GB the 32-bit fields can't hold the sizes; switch to RF64 for good
+        if !self.rf64 && file_size > self.rf64_threshold {
+            self.promote_to_rf64()?;
+        }
+
+        let (riff_size_field, data_size_field) = if self.rf64 {
+            // RF64 readers take the real sizes from ds64; the legacy fields must be -1
+            let frames = self.samples_written / self.channels.max(1) as u64;
+            self.writer
+                .seek(SeekFrom::Start(self.ds64_chunk_pos + 8))?;
+            self.writer.write_all(&file_size.to_le_bytes())?;
+            self.writer.write_all(&data_size.to_le_bytes())?;
+            self.writer.write_all(&frames.to_le_bytes())?;
+            self.writer.write_all(&0u32.to_le_bytes())?; // No table entries
+            (u32::MAX, u32::MAX)
+        } else {
+            (file_size as u32, data_size as u32)
+        };
 
         // Update RIFF chunk size
         self.writer
             .seek(SeekFrom::Start(self.riff_chunk_size_pos))?;
-        self.writer.write_all(&(file_size as u32).to_le_bytes())?;
+        self.writer.write_all(&riff_size_field.to_le_bytes())?;
 
         // Update data chunk size
         self.writer
             .seek(SeekFrom::Start(self.data_chunk_size_pos))?;
-        self.writer.write_all(&(data_size as u32).to_le_bytes())?;
+        self.writer.write_all(&data_size_field.to_le_bytes())?;
 
         // Seek back to end and flush
         self.writer.seek(SeekFrom::Start(current_pos))?;
-        self.writer.flush()?;
+        self.flush()?;
 
         debug!(
             "Updated WAV headers: {} samples written ({:.2} seconds)",
@@ -163,9 +721,24 @@ impl WavWriter {
         Ok(())
     }
 
+    /// Turn the file into RF64 (EBU Tech 3306): `RF64` replaces the `RIFF`
+    /// marker and the reserved `JUNK` chunk becomes `ds64`
+    fn promote_to_rf64(&mut self) -> io::Result<()> {
+        self.writer
+            .seek(SeekFrom::Start(self.riff_chunk_size_pos - 4))?;
+        self.writer.write_all(b"RF64")?;
+        self.writer.seek(SeekFrom::Start(self.ds64_chunk_pos))?;
+        self.writer.write_all(b"ds64")?;
+        self.rf64 = true;
+
+        info!("WAV file {:?} outgrew RIFF sizes, switched to RF64", self.file_path);
+        Ok(())
+    }
+
     /// Finalize the WAV file with correct headers
     pub fn finalize(&mut self) -> io::Result<()> {
-        self.update_headers()?;
+        let trailer_size = self.write_bext_chunk()?;
+        self.update_headers_with_trailer(trailer_size)?;
         self.writer.flush()?;
 
         info!(
@@ -178,6 +751,28 @@ impl WavWriter {
         Ok(())
     }
 
+    /// Write the `bext` chunk after the data chunk, returning its size in bytes
+    ///
+    /// The write position is restored to the end of the data afterwards, so if
+    /// recording resumes the new samples overwrite the chunk and the next
+    /// finalize writes it again after them. An odd-sized data chunk (24-bit
+    /// mono) gets the pad byte RIFF requires first, counted in the size.
+    fn write_bext_chunk(&mut self) -> io::Result<u64> {
+        let mut chunk = match &self.bext {
+            Some(bext) => bext.to_chunk(self.sample_rate, self.channels, self.bits_per_sample),
+            None => return Ok(0),
+        };
+        if (self.samples_written * self.bytes_per_sample as u64) % 2 == 1 {
+            chunk.insert(0, 0);
+        }
+
+        let data_end = self.writer.stream_position()?;
+        self.writer.write_all(&chunk)?;
+        self.writer.seek(SeekFrom::Start(data_end))?;
+
+        Ok(chunk.len() as u64)
+    }
+
     /// Get the current duration in seconds
     pub fn get_duration_seconds(&self) -> f32 {
         self.samples_written as f32 / (self.sample_rate as f32 * self.channels as f32)
@@ -188,17 +783,202 @@ impl WavWriter {
         &self.file_path
     }
 
+    /// Wall-clock time of the first captured sample, if any audio was written
+    pub fn get_first_sample_time(&self) -> Option<SystemTime> {
+        self.first_sample_time
+    }
+
+    /// Level, clipping and silence statistics for everything written so far
+    pub fn get_summary(&self) -> RecordingSummary {
+        self.summary.summary()
+    }
+
+    /// Current format, progress and write settings
+    pub fn get_status(&self) -> WavWriterStatus {
+        WavWriterStatus {
+            file_path: self.file_path.to_string_lossy().to_string(),
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            format: self.format,
+            samples_written: self.samples_written,
+            duration_seconds: self.get_duration_seconds(),
+            rf64: self.rf64,
+            dc_offset_removal: self.dc_blocker.is_some(),
+            sync_to_disk: self.sync_to_disk,
+            flush_interval_ms: self.flush_interval.as_millis() as u64,
+        }
+    }
+
     /// Get audio metadata
     pub fn get_metadata(&self) -> (u32, u16, f32) {
         (self.sample_rate, self.channels, self.get_duration_seconds())
     }
 
-    /// Flush any buffered data to disk
+    /// Flush any buffered data to the OS, and to disk if `sync_to_disk` is set
     pub fn flush(&mut self) -> io::Result<()> {
-        self.writer.flush()
+        self.writer.flush()?;
+        if self.sync_to_disk {
+            self.writer.get_ref().sync_data()?;
+        }
+        self.last_flush = Instant::now();
+        Ok(())
     }
 }
 
+/// Fields of a `WavWriter` header read back from disk
+struct RecordingHeader {
+    rf64: bool,
+    format: PcmFormat,
+    sample_rate: u32,
+    channels: u16,
+    /// `None` while the header still holds its placeholder size
+    declared_data_size: Option<u64>,
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Read and check the header of a file written by `WavWriter`
+///
+/// The layout is fixed, so anything else (or a sample format `WavWriter`
+/// doesn't write) is rejected.
+fn read_recording_header(file: &mut File) -> io::Result<RecordingHeader> {
+    let mut header = [0u8; DATA_START as usize];
+    file.seek(SeekFrom::Start(0))?;
+    file.read_exact(&mut header)
+        .map_err(|_| invalid_data("File is too short to be a recording"))?;
+
+    let rf64 = &header[0..4] == b"RF64";
+    let layout_matches = (rf64 || &header[0..4] == b"RIFF")
+        && &header[8..12] == b"WAVE"
+        && (&header[12..16] == b"JUNK" || &header[12..16] == b"ds64")
+        && &header[48..52] == b"fmt "
+        && &header[72..76] == b"data";
+    if !layout_matches {
+        return Err(invalid_data("Not a recording written by this app"));
+    }
+
+    let u16_at = |pos: usize| u16::from_le_bytes([header[pos], header[pos + 1]]);
+    let u32_at = |pos: usize| u32::from_le_bytes(header[pos..pos + 4].try_into().unwrap());
+    let u64_at = |pos: usize| u64::from_le_bytes(header[pos..pos + 8].try_into().unwrap());
+    let channels = u16_at(58);
+    let sample_rate = u32_at(60);
+    let format = PcmFormat::from_header(u16_at(56), u16_at(70))
+        .filter(|_| channels > 0 && sample_rate > 0)
+        .ok_or_else(|| invalid_data("Unexpected sample format in recording"))?;
+
+    // RF64 keeps the data size in ds64; 0xFFFFFFFF is the placeholder otherwise
+    let declared_data_size = if rf64 {
+        Some(u64_at(28))
+    } else {
+        Some(u32_at(76)).filter(|&size| size != u32::MAX).map(u64::from)
+    };
+
+    Ok(RecordingHeader {
+        rf64,
+        format,
+        sample_rate,
+        channels,
+        declared_data_size,
+    })
+}
+
+/// Bytes of sample data read at a time when rewriting a finalized file
+const NORMALIZE_BUFFER_BYTES: usize = 64 * 1024;
+
+/// Scale a finalized recording so its loudest sample reaches `target_peak`
+/// (e.g. 0.95 for 95% of full scale), returning the gain applied
+///
+/// Works in place in two passes over the file, one to find the peak and one
+/// to rewrite the samples, so memory use doesn't grow with the recording and
+/// nothing is delayed while recording. Silent recordings are left unchanged.
+/// Files still holding placeholder sizes must go through
+/// `WavWriter::recover` first.
+pub fn normalize_wav_file(path: &Path, target_peak: f32) -> io::Result<f32> {
+    if !(target_peak > 0.0 && target_peak <= 1.0) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("Target peak must be in (0, 1], got {}", target_peak),
+        ));
+    }
+
+    let mut file = File::options().read(true).write(true).open(path)?;
+    let header = read_recording_header(&mut file)?;
+    let data_size = header
+        .declared_data_size
+        .filter(|&size| DATA_START + size <= file.metadata().map(|m| m.len()).unwrap_or(0))
+        .ok_or_else(|| invalid_data("Recording headers are incomplete; recover it first"))?;
+
+    let format = header.format;
+    let bytes_per_sample = format.bits_per_sample() as usize / 8;
+    // Blocks hold whole samples, which matters for 3-byte ones
+    let mut buffer = vec![0u8; NORMALIZE_BUFFER_BYTES / bytes_per_sample * bytes_per_sample];
+    let mut peak = 0.0f32;
+    for_each_data_block(&mut file, data_size, &mut buffer, |block| {
+        for bytes in block.chunks_exact(bytes_per_sample) {
+            peak = peak.max(format.decode(bytes).abs());
+        }
+        false
+    })?;
+    if peak == 0.0 {
+        info!("WAV file {:?} is silent, leaving it unnormalized", path);
+        return Ok(1.0);
+    }
+
+    let gain = target_peak / peak;
+    let mut encoded = Vec::with_capacity(bytes_per_sample);
+    for_each_data_block(&mut file, data_size, &mut buffer, |block| {
+        for bytes in block.chunks_exact_mut(bytes_per_sample) {
+            encoded.clear();
+            format.encode(format.decode(bytes) * gain, &mut encoded);
+            bytes.copy_from_slice(&encoded);
+        }
+        true
+    })?;
+    file.flush()?;
+
+    info!(
+        "Normalized WAV file {:?}: peak {:.3} -> {:.3} (gain {:.2})",
+        path, peak, target_peak, gain
+    );
+    Ok(gain)
+}
+
+/// Read the data chunk in blocks the size of `buffer`, writing each block back
+/// when `f` returns true after changing it
+fn for_each_data_block(
+    file: &mut File,
+    data_size: u64,
+    buffer: &mut [u8],
+    mut f: impl FnMut(&mut [u8]) -> bool,
+) -> io::Result<()> {
+    let mut pos = 0;
+    while pos < data_size {
+        let len = (data_size - pos).min(buffer.len() as u64) as usize;
+        let block = &mut buffer[..len];
+        file.seek(SeekFrom::Start(DATA_START + pos))?;
+        file.read_exact(block)?;
+        if f(block) {
+            file.seek(SeekFrom::Start(DATA_START + pos))?;
+            file.write_all(block)?;
+        }
+        pos += len as u64;
+    }
+    Ok(())
+}
+
+/// Conversion used for i16 input, shared with the stream callback so processed
+/// audio matches what `write_samples_i16` would write
+pub(crate) fn i16_to_f32(sample: i16) -> f32 {
+    sample as f32 / i16::MAX as f32
+}
+
+/// Conversion used for u16 input, see `i16_to_f32`
+pub(crate) fn u16_to_f32(sample: u16) -> f32 {
+    (sample as f32 / u16::MAX as f32) * 2.0 - 1.0
+}
+
 impl Drop for WavWriter {
     fn drop(&mut self) {
         // Ensure headers are updated when the writer is dropped
@@ -208,3 +988,106 @@ impl Drop for WavWriter {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_at(bytes: &[u8], pos: usize) -> u32 {
+        u32::from_le_bytes(bytes[pos..pos + 4].try_into().unwrap())
+    }
+
+    fn u64_at(bytes: &[u8], pos: usize) -> u64 {
+        u64::from_le_bytes(bytes[pos..pos + 8].try_into().unwrap())
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("wav_writer_{}_{}.wav", name, std::process::id()))
+    }
+
+    #[test]
+    fn promotes_to_rf64_past_threshold() {
+        let path = temp_path("rf64");
+        let config = WavWriterConfig {
+            rf64_threshold_bytes: Some(1000),
+            ..WavWriterConfig::default()
+        };
+        let mut writer = WavWriter::new(path.clone(), 16000, 2, config).unwrap();
+        // 1000 stereo frames of f32: 8000 data bytes, well past the threshold
+        writer.write_samples_f32(&vec![0.25; 2000]).unwrap();
+        writer.finalize().unwrap();
+        drop(writer);
+
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[0..4], b"RF64");
+        assert_eq!(u32_at(&bytes, 4), u32::MAX);
+        assert_eq!(&bytes[12..16], b"ds64");
+        assert_eq!(u64_at(&bytes, 20), bytes.len() as u64 - 8);
+        assert_eq!(u64_at(&bytes, 28), 8000);
+        assert_eq!(u64_at(&bytes, 36), 1000);
+        assert_eq!(u32_at(&bytes, 44), 0);
+        assert_eq!(u32_at(&bytes, 76), u32::MAX);
+        assert_eq!(bytes.len() as u64, DATA_START + 8000);
+
+        // The ds64 sizes are what recovery reads back
+        let recovered = WavWriter::recover(path.clone()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert!(!recovered.repaired);
+        assert_eq!(recovered.channels, 2);
+        assert_eq!(recovered.sample_rate, 16000);
+        assert!((recovered.duration_seconds - 1000.0 / 16000.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn removes_dc_offset_per_channel() {
+        let path = temp_path("dc_offset");
+        let config = WavWriterConfig {
+            remove_dc_offset: true,
+            ..WavWriterConfig::default()
+        };
+        let sample_rate = 16000;
+        let mut writer = WavWriter::new(path.clone(), sample_rate, 2, config).unwrap();
+
+        // Two seconds of a 440 Hz tone, offset by +0.3 on the left and -0.3 on
+        // the right, in odd-sized buffers so frames straddle buffer boundaries
+        let samples: Vec<f32> = (0..2 * sample_rate as usize)
+            .flat_map(|n| {
+                let t = n as f32 / sample_rate as f32;
+                let tone = 0.2 * (2.0 * std::f32::consts::PI * 440.0 * t).sin();
+                [tone + 0.3, tone - 0.3]
+            })
+            .collect();
+        for buffer in samples.chunks(1023) {
+            writer.write_samples_f32(buffer).unwrap();
+        }
+        writer.finalize().unwrap();
+        drop(writer);
+
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let written: Vec<f32> = bytes[DATA_START as usize..]
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        assert_eq!(written.len(), samples.len());
+
+        // Once the filter has settled, each channel is centred on zero
+        let settled = &written[written.len() / 2..];
+        for channel in 0..2 {
+            let channel_samples: Vec<f32> =
+                settled.iter().skip(channel).step_by(2).copied().collect();
+            let mean = channel_samples.iter().sum::<f32>() / channel_samples.len() as f32;
+            let peak = channel_samples
+                .iter()
+                .fold(0.0f32, |peak, s| peak.max(s.abs()));
+            assert!(mean.abs() < 0.005, "channel {} mean {}", channel, mean);
+            // The tone itself passes through
+            assert!(
+                (peak - 0.2).abs() < 0.01,
+                "channel {} peak {}",
+                channel,
+                peak
+            );
+        }
+    }
+}
@@ -0,0 +1,282 @@
+use super::error::TranscriptionError;
+use super::whisper_cpp::WhisperOptions;
+use super::{
+    convert_audio_for_whisper, export, transcribe_whisper_full, ModelManager, ResampleQuality,
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+/// Event emitted after each file of a `convert_folder` batch
+pub const CONVERT_FOLDER_PROGRESS_EVENT: &str = "convert-folder-progress";
+
+/// Extensions treated as audio when scanning a folder
+const AUDIO_EXTENSIONS: [&str; 12] = [
+    "wav", "mp3", "m4a", "ogg", "opus", "flac", "webm", "mp4", "aac", "wma", "aif", "aiff",
+];
+
+/// Progress for one file of a batch - emitted to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertFolderProgress {
+    /// 1-based position of this file in the batch
+    pub index: usize,
+    pub total: usize,
+    pub input_path: String,
+    pub output_path: Option<String>,
+    pub error: Option<String>,
+}
+
+/// A file that could not be converted
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionFailure {
+    pub input_path: String,
+    pub error: String,
+}
+
+/// Outcome of a whole `convert_folder` batch - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertFolderSummary {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failures: Vec<ConversionFailure>,
+}
+
+/// Transcript written next to each file by `transcribe_directory`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SidecarFormat {
+    Txt,
+    Srt,
+}
+
+impl SidecarFormat {
+    fn parse(format: &str) -> Option<Self> {
+        match format.to_lowercase().as_str() {
+            "txt" => Some(SidecarFormat::Txt),
+            "srt" => Some(SidecarFormat::Srt),
+            _ => None,
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            SidecarFormat::Txt => "txt",
+            SidecarFormat::Srt => "srt",
+        }
+    }
+}
+
+/// Outcome for one file of a `transcribe_directory` batch - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchTranscriptionResult {
+    pub file_name: String,
+    /// Empty when the file failed
+    pub text: String,
+    pub duration_secs: f32,
+    pub error: Option<String>,
+}
+
+/// Audio files directly inside `dir`, sorted by name
+fn audio_files(dir: &Path) -> std::io::Result<Vec<PathBuf>> {
+    files_with_extensions(dir, &AUDIO_EXTENSIONS)
+}
+
+/// Files directly inside `dir` with one of `extensions` (case-insensitive), sorted by name
+fn files_with_extensions(dir: &Path, extensions: &[&str]) -> std::io::Result<Vec<PathBuf>> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| extensions.contains(&ext.to_lowercase().as_str()))
+        })
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Convert one file, returning the path of the written WAV
+fn convert_file(input: &Path, output_dir: &Path) -> Result<PathBuf, String> {
+    let audio_data = std::fs::read(input).map_err(|e| format!("Failed to read file: {}", e))?;
+    let wav_data =
+        convert_audio_for_whisper(audio_data, ResampleQuality::Auto, None).map_err(|e| e.to_string())?;
+
+    let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+    let output = output_dir.join(format!("{}.wav", stem));
+    std::fs::write(&output, wav_data).map_err(|e| format!("Failed to write {:?}: {}", output, e))?;
+    Ok(output)
+}
+
+/// Convert every audio file in a folder to 16kHz mono 16-bit WAV
+///
+/// Uses the same three-tier conversion as transcription. Files are written to
+/// `output_dir` with a `.wav` extension, and a `convert-folder-progress` event
+/// is emitted after each one. A failing file is recorded in the summary and
+/// the batch carries on. Subfolders are not scanned.
+#[tauri::command]
+pub async fn convert_folder(
+    input_dir: String,
+    output_dir: String,
+    app_handle: AppHandle,
+) -> Result<ConvertFolderSummary, TranscriptionError> {
+    let input_dir = PathBuf::from(input_dir);
+    let output_dir = PathBuf::from(output_dir);
+
+    let files = audio_files(&input_dir).map_err(|e| {
+        let message = format!("Failed to read folder {:?}: {}", input_dir, e);
+        TranscriptionError::audio_read_from(message, e)
+    })?;
+
+    std::fs::create_dir_all(&output_dir).map_err(|e| {
+        let message = format!("Failed to create output folder {:?}: {}", output_dir, e);
+        TranscriptionError::audio_read_from(message, e)
+    })?;
+
+    // Writing next to the inputs would overwrite WAV sources with their converted copies
+    if input_dir.canonicalize().ok() == output_dir.canonicalize().ok() {
+        return Err(TranscriptionError::audio_read(
+            "Output folder must be different from the input folder".to_string(),
+        ));
+    }
+
+    let total = files.len();
+    let mut summary = ConvertFolderSummary {
+        total,
+        succeeded: 0,
+        failures: Vec::new(),
+    };
+
+    println!("[Convert Folder] Converting {} files from {:?} to {:?}", total, input_dir, output_dir);
+
+    for (i, input) in files.iter().enumerate() {
+        let input_path = input.to_string_lossy().to_string();
+        let (output_path, error) = match convert_file(input, &output_dir) {
+            Ok(output) => {
+                summary.succeeded += 1;
+                (Some(output.to_string_lossy().to_string()), None)
+            }
+            Err(e) => {
+                eprintln!("[Convert Folder] Failed to convert {}: {}", input_path, e);
+                summary.failures.push(ConversionFailure {
+                    input_path: input_path.clone(),
+                    error: e.clone(),
+                });
+                (None, Some(e))
+            }
+        };
+
+        let _ = app_handle.emit(
+            CONVERT_FOLDER_PROGRESS_EVENT,
+            ConvertFolderProgress {
+                index: i + 1,
+                total,
+                input_path,
+                output_path,
+                error,
+            },
+        );
+    }
+
+    println!(
+        "[Convert Folder] Done: {} of {} converted, {} failed",
+        summary.succeeded,
+        total,
+        summary.failures.len()
+    );
+
+    Ok(summary)
+}
+
+/// Transcribe one file and write its sidecar transcript, returning the text and duration
+fn transcribe_file(
+    input: &Path,
+    model_path: &str,
+    language: Option<&str>,
+    format: SidecarFormat,
+    model_manager: &ModelManager,
+) -> Result<(String, f32), String> {
+    let audio_data = std::fs::read(input).map_err(|e| format!("Failed to read file: {}", e))?;
+    let (result, segments) = transcribe_whisper_full(
+        audio_data,
+        model_path,
+        language,
+        WhisperOptions::default(),
+        model_manager,
+        None,
+    )
+    .map_err(|e| e.to_string())?;
+
+    let sidecar = match format {
+        SidecarFormat::Txt => format!("{}\n", result.text),
+        SidecarFormat::Srt => export::format_as_srt(&segments),
+    };
+    let output = input.with_extension(format.extension());
+    std::fs::write(&output, sidecar).map_err(|e| format!("Failed to write {:?}: {}", output, e))?;
+
+    Ok((result.text, result.duration_ms as f32 / 1000.0))
+}
+
+/// Transcribe every WAV file in a folder, writing a transcript next to each
+///
+/// `output_format` is `txt` or `srt`; `talk.wav` gets `talk.txt` or
+/// `talk.srt`, replacing any existing one. Files go through the usual
+/// conversion and share the model cached by the `ModelManager`, so it is
+/// loaded once for the whole batch. A failing file is reported in its result
+/// and the batch carries on. Subfolders are not scanned.
+#[tauri::command]
+pub async fn transcribe_directory(
+    dir_path: String,
+    model_path: String,
+    language: Option<String>,
+    output_format: String,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<Vec<BatchTranscriptionResult>, TranscriptionError> {
+    let format = SidecarFormat::parse(&output_format).ok_or_else(|| {
+        TranscriptionError::TranscriptionError {
+            message: format!("Unknown output format '{}'; expected txt or srt", output_format),
+        }
+    })?;
+
+    let dir = PathBuf::from(dir_path);
+    let files = files_with_extensions(&dir, &["wav"]).map_err(|e| {
+        TranscriptionError::audio_read_from(format!("Failed to read folder {:?}: {}", dir, e), e)
+    })?;
+
+    println!("[Transcribe Directory] Transcribing {} files in {:?}", files.len(), dir);
+
+    let results: Vec<BatchTranscriptionResult> = files
+        .iter()
+        .map(|input| {
+            let file_name = input.file_name().unwrap_or_default().to_string_lossy().to_string();
+            match transcribe_file(input, &model_path, language.as_deref(), format, &model_manager) {
+                Ok((text, duration_secs)) => BatchTranscriptionResult {
+                    file_name,
+                    text,
+                    duration_secs,
+                    error: None,
+                },
+                Err(e) => {
+                    eprintln!("[Transcribe Directory] Failed to transcribe {}: {}", file_name, e);
+                    BatchTranscriptionResult {
+                        file_name,
+                        text: String::new(),
+                        duration_secs: 0.0,
+                        error: Some(e),
+                    }
+                }
+            }
+        })
+        .collect();
+
+    println!(
+        "[Transcribe Directory] Done: {} of {} transcribed",
+        results.iter().filter(|result| result.error.is_none()).count(),
+        results.len()
+    );
+
+    Ok(results)
+}
@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Flag checked by long-running work to see whether it should stop early
+#[derive(Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Tokens for in-flight cancellable operations, keyed by a caller-chosen ID
+pub struct CancellationTokens {
+    tokens: Mutex<HashMap<String, CancellationToken>>,
+}
+
+impl CancellationTokens {
+    pub fn new() -> Self {
+        Self {
+            tokens: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Create a fresh token for `id`, replacing any stale one with the same ID
+    pub fn register(&self, id: &str) -> CancellationToken {
+        let token = CancellationToken::default();
+        self.tokens
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), token.clone());
+        token
+    }
+
+    /// Signal cancellation; returns false if no operation is registered under `id`
+    pub fn cancel(&self, id: &str) -> bool {
+        match self.tokens.lock().unwrap().get(id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a token once its operation has finished
+    pub fn remove(&self, id: &str) {
+        self.tokens.lock().unwrap().remove(id);
+    }
+}
@@ -0,0 +1,70 @@
+use super::ffmpeg::ffmpeg_available;
+use serde::Serialize;
+
+/// GPU backends whisper.cpp can report in its system info, in display form
+const GPU_BACKENDS: [&str; 6] = ["CUDA", "Metal", "Vulkan", "CoreML", "OpenVINO", "BLAS"];
+
+/// What this build of the app can do - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    /// GPU/accelerator backends whisper.cpp was compiled with
+    pub gpu_backends: Vec<String>,
+    /// Transcription engines available (`whisper` runs on whisper.cpp through
+    /// whisper-rs, `parakeet` on transcribe-rs)
+    pub engines: Vec<String>,
+    /// Formats decoded in-process without FFmpeg
+    pub native_decode_formats: Vec<String>,
+    /// Pure-Rust MP3 decoding (e.g. symphonia) is compiled in
+    pub mp3_decode: bool,
+    /// Recordings can be written as FLAC
+    pub flac_writing: bool,
+    /// An `ffmpeg` binary could be run, so other formats can be converted
+    pub ffmpeg_available: bool,
+    /// whisper.cpp's raw system info line, for diagnostics
+    pub whisper_system_info: String,
+}
+
+/// Backends that whisper.cpp's system info reports as enabled
+///
+/// Older versions print `CUDA = 1`, newer ones only list the compiled
+/// backends (e.g. `CUDA : ARCHS = 890`), so both forms are accepted.
+fn enabled_gpu_backends(system_info: &str) -> Vec<String> {
+    let entries: Vec<(String, String)> = system_info
+        .split('|')
+        .filter_map(|entry| {
+            let (name, value) = entry.split_once(['=', ':'])?;
+            Some((name.trim().to_uppercase(), value.trim().to_string()))
+        })
+        .collect();
+
+    GPU_BACKENDS
+        .iter()
+        .filter(|backend| {
+            entries
+                .iter()
+                .any(|(name, value)| *name == backend.to_uppercase() && value != "0")
+        })
+        .map(|backend| backend.to_string())
+        .collect()
+}
+
+/// Report the features compiled into the running binary
+///
+/// Lets the UI enable or hide features up front instead of discovering
+/// failures at runtime. Everything except `ffmpegAvailable` is fixed at build
+/// time.
+#[tauri::command]
+pub fn get_capabilities() -> Capabilities {
+    let whisper_system_info = whisper_rs::print_system_info().trim().to_string();
+
+    Capabilities {
+        gpu_backends: enabled_gpu_backends(&whisper_system_info),
+        engines: vec!["whisper".to_string(), "parakeet".to_string()],
+        native_decode_formats: vec!["wav".to_string()],
+        mp3_decode: false,
+        flac_writing: false,
+        ffmpeg_available: ffmpeg_available(),
+        whisper_system_info,
+    }
+}
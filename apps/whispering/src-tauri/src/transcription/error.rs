@@ -1,11 +1,26 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, Serializer};
 use thiserror::Error;
 
+/// Underlying error kept as the `source` of a `TranscriptionError`
+pub type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
 #[derive(Error, Debug, Serialize, Deserialize)]
 #[serde(tag = "name")]
 pub enum TranscriptionError {
     #[error("Audio read error: {message}")]
-    AudioReadError { message: String },
+    AudioReadError {
+        message: String,
+        #[source]
+        #[serde(serialize_with = "serialize_source", skip_deserializing)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source: Option<BoxedError>,
+    },
+
+    #[error("Audio format not supported: {message}")]
+    AudioFormatNotSupported { message: String },
+
+    #[error("Cancelled: {message}")]
+    Cancelled { message: String },
 
     #[error("FFmpeg not found: {message}")]
     FfmpegNotFoundError { message: String },
@@ -16,6 +31,118 @@ pub enum TranscriptionError {
     #[error("Model load error: {message}")]
     ModelLoadError { message: String },
 
+    #[error("Out of memory: {message}")]
+    OutOfMemory { message: String },
+
+    #[error("Prompt file read error: {message}")]
+    PromptFileReadError {
+        message: String,
+        #[source]
+        #[serde(serialize_with = "serialize_source", skip_deserializing)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        source: Option<BoxedError>,
+    },
+
     #[error("Transcription error: {message}")]
     TranscriptionError { message: String },
-}
\ No newline at end of file
+}
+
+/// Send the source to the frontend as its message chain, outermost first
+fn serialize_source<S: Serializer>(
+    source: &Option<BoxedError>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let Some(source) = source else {
+        return serializer.serialize_none();
+    };
+
+    let mut chain = source.to_string();
+    let mut next = source.source();
+    while let Some(cause) = next {
+        chain.push_str(": ");
+        chain.push_str(&cause.to_string());
+        next = cause.source();
+    }
+    serializer.serialize_some(&chain)
+}
+
+/// Fragments of allocation failures reported by whisper.cpp/ggml backends
+/// (CUDA, Metal, Vulkan) and ONNX Runtime, matched case-insensitively
+const OUT_OF_MEMORY_PATTERNS: [&str; 8] = [
+    "out of memory",
+    "outofmemory",
+    "out_of_memory",
+    "failed to allocate",
+    "allocation failed",
+    "cudamalloc",
+    "insufficient memory",
+    "not enough memory",
+];
+
+/// Whether an engine error message describes running out of (GPU) memory
+fn is_out_of_memory(message: &str) -> bool {
+    let message = message.to_lowercase();
+    OUT_OF_MEMORY_PATTERNS.iter().any(|pattern| message.contains(pattern))
+}
+
+impl TranscriptionError {
+    /// Report an engine failure, or `OutOfMemory` if the message says memory ran out
+    ///
+    /// Engines only give us strings, so OOM is recognised from the message.
+    /// The hint lets the frontend offer a smaller model or a CPU retry instead
+    /// of a generic failure.
+    fn from_engine(message: String, otherwise: fn(String) -> Self) -> Self {
+        if is_out_of_memory(&message) {
+            return TranscriptionError::OutOfMemory {
+                message: format!(
+                    "{}. Try a smaller model, or run on the CPU if the GPU is out of memory",
+                    message
+                ),
+            };
+        }
+        otherwise(message)
+    }
+
+    /// A model failed to load
+    pub fn model_load(message: String) -> Self {
+        Self::from_engine(message, |message| TranscriptionError::ModelLoadError { message })
+    }
+
+    /// Inference failed
+    pub fn transcription(message: String) -> Self {
+        Self::from_engine(message, |message| TranscriptionError::TranscriptionError { message })
+    }
+
+    /// Audio couldn't be read or converted, with no underlying error to keep
+    pub fn audio_read(message: String) -> Self {
+        TranscriptionError::AudioReadError { message, source: None }
+    }
+
+    /// Audio couldn't be read or converted because of `source`, which stays
+    /// reachable through `Error::source`
+    pub fn audio_read_from(message: String, source: impl Into<BoxedError>) -> Self {
+        TranscriptionError::AudioReadError {
+            message,
+            source: Some(source.into()),
+        }
+    }
+
+    /// Prefix the message with what was being done, e.g. the file involved
+    ///
+    /// The variant and any source error are kept.
+    pub fn with_context(mut self, ctx: &str) -> Self {
+        let message = match &mut self {
+            TranscriptionError::AudioReadError { message, .. }
+            | TranscriptionError::AudioFormatNotSupported { message }
+            | TranscriptionError::Cancelled { message }
+            | TranscriptionError::FfmpegNotFoundError { message }
+            | TranscriptionError::GpuError { message }
+            | TranscriptionError::ModelLoadError { message }
+            | TranscriptionError::OutOfMemory { message }
+            | TranscriptionError::PromptFileReadError { message, .. }
+            | TranscriptionError::TranscriptionError { message } => message,
+        };
+        *message = format!("{}: {}", ctx, message);
+        self
+    }
+}
@@ -0,0 +1,268 @@
+use serde::{Deserialize, Serialize};
+
+/// Number of words used to build a chapter title from its text
+const CHAPTER_TITLE_WORDS: usize = 6;
+
+/// A timed span of transcript text
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Segment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub text: String,
+}
+
+/// A single word with its timing and decoder confidence
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WordTimestamp {
+    pub word: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Mean probability of the word's tokens
+    pub probability: f32,
+}
+
+/// A complete transcript with per-segment metadata - returned to frontend
+///
+/// Meant as a stable JSON format for scripts and other apps, so fields are
+/// only ever added, never renamed or removed. `Display` renders the text.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptDocument {
+    pub text: String,
+    /// Language whisper decoded in, e.g. `en`
+    pub language: Option<String>,
+    /// Duration of the transcribed audio
+    pub duration_ms: u64,
+    pub segments: Vec<TranscriptSegment>,
+}
+
+/// One segment of a `TranscriptDocument`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSegment {
+    pub index: u32,
+    pub text: String,
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Probability that the segment holds no speech. whisper-rs 0.13 doesn't
+    /// expose whisper.cpp's per-segment value, so this is 0.0 until it does.
+    pub no_speech_prob: f32,
+}
+
+impl TranscriptDocument {
+    pub fn new(
+        text: String,
+        language: Option<String>,
+        duration_ms: u64,
+        segments: Vec<Segment>,
+    ) -> Self {
+        let segments = segments
+            .into_iter()
+            .enumerate()
+            .map(|(index, segment)| TranscriptSegment {
+                index: index as u32,
+                text: segment.text,
+                start_ms: segment.start_ms,
+                end_ms: segment.end_ms,
+                no_speech_prob: 0.0,
+            })
+            .collect();
+        Self {
+            text,
+            language,
+            duration_ms,
+            segments,
+        }
+    }
+}
+
+impl std::fmt::Display for TranscriptDocument {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Chapter file formats supported by `export_chapters`
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ChapterFormat {
+    /// FFmpeg metadata file (`;FFMETADATA1`), usable with `ffmpeg -i meta.txt -map_metadata 1`
+    Ffmetadata,
+    /// One `HH:MM:SS Title` line per chapter, as used by podcast and video platforms
+    Simple,
+}
+
+/// Build a chapter title from the first few words of a segment
+fn chapter_title(text: &str, index: usize) -> String {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return format!("Chapter {}", index + 1);
+    }
+
+    let mut title = words[..words.len().min(CHAPTER_TITLE_WORDS)].join(" ");
+    if words.len() > CHAPTER_TITLE_WORDS {
+        title.push('…');
+    }
+    title
+}
+
+/// Format milliseconds as `HH:MM:SS`
+fn format_hms(ms: i64) -> String {
+    let total_secs = ms.max(0) / 1000;
+    format!(
+        "{:02}:{:02}:{:02}",
+        total_secs / 3600,
+        (total_secs / 60) % 60,
+        total_secs % 60
+    )
+}
+
+/// Format milliseconds as a subtitle timestamp, `HH:MM:SS<separator>mmm`
+fn format_subtitle_timestamp(ms: i64, separator: char) -> String {
+    let ms = ms.max(0);
+    format!("{}{}{:03}", format_hms(ms), separator, ms % 1000)
+}
+
+/// Timing line and text of a subtitle cue, for each non-empty segment
+fn subtitle_cues(segments: &[Segment], separator: char) -> impl Iterator<Item = String> + '_ {
+    segments
+        .iter()
+        .filter(|segment| !segment.text.trim().is_empty())
+        .map(move |segment| {
+            format!(
+                "{} --> {}\n{}\n",
+                format_subtitle_timestamp(segment.start_ms, separator),
+                format_subtitle_timestamp(segment.end_ms.max(segment.start_ms), separator),
+                segment.text.trim()
+            )
+        })
+}
+
+/// Render segments as SRT subtitles, one numbered cue per non-empty segment
+pub fn format_as_srt(segments: &[Segment]) -> String {
+    subtitle_cues(segments, ',')
+        .enumerate()
+        .map(|(index, cue)| format!("{}\n{}\n", index + 1, cue))
+        .collect()
+}
+
+/// Render segments as WebVTT subtitles, one cue per non-empty segment
+///
+/// Same cues as `format_as_srt`, under a `WEBVTT` header, with `.` before
+/// the milliseconds and without the optional cue identifiers.
+pub fn format_as_vtt(segments: &[Segment]) -> String {
+    let mut output = String::from("WEBVTT\n\n");
+    for cue in subtitle_cues(segments, '.') {
+        output.push_str(&cue);
+        output.push('\n');
+    }
+    output
+}
+
+/// Escape characters that have special meaning in FFmpeg metadata files
+fn escape_ffmetadata(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '=' | ';' | '#' | '\\' | '\n') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Render segments as a chapter file
+pub fn format_chapters(segments: &[Segment], format: ChapterFormat) -> String {
+    match format {
+        ChapterFormat::Ffmetadata => {
+            let mut output = String::from(";FFMETADATA1\n");
+            for (index, segment) in segments.iter().enumerate() {
+                output.push_str("\n[CHAPTER]\nTIMEBASE=1/1000\n");
+                output.push_str(&format!("START={}\n", segment.start_ms.max(0)));
+                output.push_str(&format!("END={}\n", segment.end_ms.max(segment.start_ms).max(0)));
+                output.push_str(&format!(
+                    "title={}\n",
+                    escape_ffmetadata(&chapter_title(&segment.text, index))
+                ));
+            }
+            output
+        }
+        ChapterFormat::Simple => segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                format!(
+                    "{} {}\n",
+                    format_hms(segment.start_ms),
+                    chapter_title(&segment.text, index)
+                )
+            })
+            .collect(),
+    }
+}
+
+/// Export transcript segments (or silence-delimited regions) as chapters
+///
+/// Each segment becomes one chapter, titled with its first few words.
+#[tauri::command]
+pub fn export_chapters(segments: Vec<Segment>, format: ChapterFormat) -> String {
+    format_chapters(&segments, format)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(start_ms: i64, end_ms: i64, text: &str) -> Segment {
+        Segment {
+            start_ms,
+            end_ms,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn srt_timestamps_roll_over() {
+        assert_eq!(format_subtitle_timestamp(0, ','), "00:00:00,000");
+        assert_eq!(format_subtitle_timestamp(59_999, ','), "00:00:59,999");
+        assert_eq!(format_subtitle_timestamp(60_000, ','), "00:01:00,000");
+        assert_eq!(format_subtitle_timestamp(3_599_999, ','), "00:59:59,999");
+        assert_eq!(format_subtitle_timestamp(3_600_000, ','), "01:00:00,000");
+        assert_eq!(format_subtitle_timestamp(-5, ','), "00:00:00,000");
+    }
+
+    #[test]
+    fn srt_numbers_cues_consecutively() {
+        let segments = [
+            segment(0, 1_500, " Hello there. "),
+            segment(1_500, 2_000, "  "),
+            segment(59_500, 61_250, "Second cue"),
+        ];
+        assert_eq!(
+            format_as_srt(&segments),
+            "1\n00:00:00,000 --> 00:00:01,500\nHello there.\n\n\
+             2\n00:00:59,500 --> 00:01:01,250\nSecond cue\n\n"
+        );
+    }
+
+    #[test]
+    fn vtt_has_header_and_dotted_timestamps() {
+        let segments = [
+            segment(0, 1_500, "Hello there."),
+            segment(3_599_000, 3_601_042, "Past the hour"),
+        ];
+        assert_eq!(
+            format_as_vtt(&segments),
+            "WEBVTT\n\n\
+             00:00:00.000 --> 00:00:01.500\nHello there.\n\n\
+             00:59:59.000 --> 01:00:01.042\nPast the hour\n\n"
+        );
+    }
+
+    #[test]
+    fn vtt_without_cues_is_just_the_header() {
+        assert_eq!(format_as_vtt(&[segment(0, 500, " ")]), "WEBVTT\n\n");
+    }
+}
@@ -0,0 +1,165 @@
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::RwLock;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+/// Where to find the FFmpeg binary
+#[derive(Debug, Clone, Default)]
+pub struct FfmpegConfig {
+    /// Explicit binary to run; `ffmpeg` is looked up on PATH when unset
+    pub binary_path: Option<PathBuf>,
+}
+
+lazy_static! {
+    /// App-wide FFmpeg configuration, read by every FFmpeg invocation
+    static ref FFMPEG_CONFIG: RwLock<FfmpegConfig> = RwLock::new(FfmpegConfig::default());
+}
+
+/// Whether FFmpeg can be run, and which one - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FfmpegInfo {
+    pub available: bool,
+    /// Version from the first line of `ffmpeg -version`, e.g. `6.1.1`
+    pub version: Option<String>,
+    /// The configured binary, or the one `ffmpeg` resolves to on PATH
+    pub path: Option<String>,
+}
+
+/// A `Command` for FFmpeg: the configured binary, or `ffmpeg` from PATH
+///
+/// On Windows the process is created without a console window.
+pub(crate) fn ffmpeg_command() -> Command {
+    let binary_path = FFMPEG_CONFIG.read().unwrap().binary_path.clone();
+    let mut cmd = match binary_path {
+        Some(path) => Command::new(path),
+        None => Command::new("ffmpeg"),
+    };
+    #[cfg(target_os = "windows")]
+    {
+        cmd.creation_flags(super::CREATE_NO_WINDOW);
+    }
+    cmd
+}
+
+/// Whether an `ffmpeg` binary can be run
+pub(crate) fn ffmpeg_available() -> bool {
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-version");
+    cmd.output().map(|output| output.status.success()).unwrap_or(false)
+}
+
+/// Whether `path` is a file the current user may execute
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        true
+    }
+}
+
+/// Find `program` the way `Command::new` does: the first PATH entry holding it
+///
+/// On Windows the extensions in `PATHEXT` are tried as well.
+fn find_in_path(program: &str) -> Option<PathBuf> {
+    let path = std::env::var_os("PATH")?;
+
+    #[cfg(target_os = "windows")]
+    let candidates: Vec<String> = std::env::var("PATHEXT")
+        .unwrap_or_else(|_| ".EXE;.CMD;.BAT;.COM".to_string())
+        .split(';')
+        .filter(|ext| !ext.is_empty())
+        .map(|ext| format!("{}{}", program, ext.to_lowercase()))
+        .chain(std::iter::once(program.to_string()))
+        .collect();
+    #[cfg(not(target_os = "windows"))]
+    let candidates = vec![program.to_string()];
+
+    std::env::split_paths(&path)
+        .flat_map(|dir| candidates.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Version from the first line of `ffmpeg -version` output
+///
+/// The line reads `ffmpeg version <version> Copyright ...`.
+fn parse_version(output: &str) -> Option<String> {
+    let first_line = output.lines().next()?;
+    let mut words = first_line.split_whitespace();
+    match (words.next(), words.next(), words.next()) {
+        (Some("ffmpeg"), Some("version"), Some(version)) => Some(version.to_string()),
+        _ => None,
+    }
+}
+
+/// Check whether FFmpeg is installed, and report its version and location
+///
+/// Runs `ffmpeg -version` so the frontend can show an "Install FFmpeg" prompt
+/// before the user tries to transcribe a format that needs it, rather than
+/// after a `FfmpegNotFoundError`.
+#[tauri::command]
+pub async fn check_ffmpeg_available() -> Result<FfmpegInfo, String> {
+    let mut cmd = ffmpeg_command();
+    cmd.arg("-version");
+
+    let path = FFMPEG_CONFIG
+        .read()
+        .unwrap()
+        .binary_path
+        .clone()
+        .or_else(|| find_in_path("ffmpeg"))
+        .map(|path| path.to_string_lossy().to_string());
+    let info = match cmd.output() {
+        Ok(output) if output.status.success() => FfmpegInfo {
+            available: true,
+            version: parse_version(&String::from_utf8_lossy(&output.stdout)),
+            path,
+        },
+        _ => FfmpegInfo {
+            available: false,
+            version: None,
+            path,
+        },
+    };
+
+    println!(
+        "[FFmpeg] available={}, version={:?}, path={:?}",
+        info.available, info.version, info.path
+    );
+    Ok(info)
+}
+
+/// Use the FFmpeg binary at `path` instead of looking it up on PATH
+///
+/// For installs in a non-standard location or bundled alongside the app. The
+/// path must be an executable file. An empty string goes back to PATH lookup.
+#[tauri::command]
+pub fn set_ffmpeg_path(path: String) -> Result<(), String> {
+    let binary_path = if path.trim().is_empty() {
+        None
+    } else {
+        let path = PathBuf::from(path.trim());
+        if !is_executable(&path) {
+            return Err(format!("{} is not an executable file", path.display()));
+        }
+        Some(path)
+    };
+
+    println!("[FFmpeg] Binary path set to {:?}", binary_path);
+    FFMPEG_CONFIG.write().unwrap().binary_path = binary_path;
+    Ok(())
+}
@@ -0,0 +1,110 @@
+use serde::Serialize;
+#[cfg(target_os = "windows")]
+use std::os::windows::process::CommandExt;
+
+#[cfg(target_os = "windows")]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+/// Memory of a single GPU device
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuMemoryInfo {
+    pub index: u32,
+    pub name: String,
+    pub total_bytes: u64,
+    pub free_bytes: u64,
+}
+
+/// GPU memory report - returned to frontend
+///
+/// `queryable` is false when the platform gives us no way to read VRAM; the
+/// frontend should then treat GPU memory as unknown rather than zero.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpuMemoryReport {
+    pub queryable: bool,
+    pub devices: Vec<GpuMemoryInfo>,
+    pub message: Option<String>,
+}
+
+impl GpuMemoryReport {
+    fn unknown(message: impl Into<String>) -> Self {
+        Self {
+            queryable: false,
+            devices: Vec::new(),
+            message: Some(message.into()),
+        }
+    }
+}
+
+/// Query NVIDIA GPUs through `nvidia-smi`, which ships with the CUDA driver
+fn query_nvidia_smi() -> Result<Vec<GpuMemoryInfo>, String> {
+    let mut cmd = std::process::Command::new("nvidia-smi");
+    cmd.args([
+        "--query-gpu=index,name,memory.total,memory.free",
+        "--format=csv,noheader,nounits",
+    ]);
+    #[cfg(target_os = "windows")]
+    {
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("nvidia-smi is not available: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "nvidia-smi failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    const MIB: u64 = 1024 * 1024;
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let fields: Vec<&str> = line.split(',').map(|f| f.trim()).collect();
+            if fields.len() != 4 {
+                return Err(format!("Unexpected nvidia-smi output: {}", line));
+            }
+            let parse = |value: &str| {
+                value
+                    .parse::<u64>()
+                    .map_err(|e| format!("Failed to parse '{}' from nvidia-smi: {}", value, e))
+            };
+            Ok(GpuMemoryInfo {
+                index: parse(fields[0])? as u32,
+                name: fields[1].to_string(),
+                total_bytes: parse(fields[2])? * MIB,
+                free_bytes: parse(fields[3])? * MIB,
+            })
+        })
+        .collect()
+}
+
+/// Report total and free VRAM per GPU, where the platform allows it
+///
+/// Only NVIDIA GPUs (via `nvidia-smi`) can currently be queried. On Apple
+/// Silicon the GPU shares system memory, so there is no separate VRAM figure.
+#[tauri::command]
+pub async fn get_gpu_memory() -> GpuMemoryReport {
+    if cfg!(target_os = "macos") {
+        return GpuMemoryReport::unknown(
+            "GPU memory is shared with system memory on macOS and can't be queried separately",
+        );
+    }
+
+    match query_nvidia_smi() {
+        Ok(devices) if devices.is_empty() => GpuMemoryReport::unknown("No NVIDIA GPUs reported"),
+        Ok(devices) => GpuMemoryReport {
+            queryable: true,
+            devices,
+            message: None,
+        },
+        Err(e) => {
+            println!("[GPU] Could not query GPU memory: {}", e);
+            GpuMemoryReport::unknown(e)
+        }
+    }
+}
@@ -0,0 +1,416 @@
+use super::error::TranscriptionError;
+use super::export::WordTimestamp;
+use super::verify::{edit_distance, normalize_word};
+use super::whisper_cpp::WhisperOptions;
+use super::{whisper_samples, with_whisper_engine, ModelManager};
+use serde::{Deserialize, Serialize};
+
+/// whisper samples per second
+const SAMPLE_RATE: f32 = 16000.0;
+
+/// Upper bound on speech rate, used to size the overlap search in words
+const MAX_WORDS_PER_SEC: f32 = 5.0;
+
+/// Largest share of mismatched words for two word runs to count as the same
+/// overlapping speech
+const MAX_OVERLAP_ERROR: f64 = 0.3;
+
+/// Largest difference between the times two chunks give a word for it to
+/// count as the same word heard twice
+const ANCHOR_TOLERANCE_MS: i64 = 400;
+
+/// Which chunk's words are kept where neighbouring chunks overlap
+///
+/// Both use word timestamps to find a word both chunks heard at the same time
+/// and join the chunks there, so words aren't doubled or dropped at the seam.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum OverlapStrategy {
+    /// Trust the later chunk, which heard what followed the overlap; joins at
+    /// the first shared word
+    PreferLater,
+    /// Trust the earlier chunk, which heard what led up to the overlap; joins
+    /// at the last shared word
+    PreferEarlier,
+}
+
+/// Options for `transcribe_long_audio`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LongAudioOptions {
+    /// Let whisper detect the language of every chunk instead of decoding the
+    /// whole recording in `language`, for speakers who switch languages
+    pub per_chunk_language_detection: Option<bool>,
+    /// Join overlapping chunks by word timestamps instead of matching their
+    /// text, keeping the preferred chunk's words in the overlap
+    pub overlap_strategy: Option<OverlapStrategy>,
+}
+
+/// Where a chunk of a long recording lies and what language it was decoded in
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LongAudioChunk {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    /// Language whisper decoded the chunk in, e.g. `en`
+    pub language: Option<String>,
+}
+
+/// Stitched transcript of a long recording - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LongAudioTranscript {
+    pub text: String,
+    /// Every chunk in order, including ones without speech
+    pub chunks: Vec<LongAudioChunk>,
+}
+
+/// Milliseconds at `sample` of 16kHz audio
+fn sample_ms(sample: usize) -> i64 {
+    (sample as u64 * 1000 / SAMPLE_RATE as u64) as i64
+}
+
+/// The last sentence of `text`, fed to the next chunk as its initial prompt
+fn last_sentence(text: &str) -> &str {
+    let text = text.trim();
+    // Skip the final character so a trailing full stop doesn't end the search
+    let body = &text[..text.len() - text.chars().last().map_or(0, char::len_utf8)];
+    match body.rfind(['.', '?', '!']) {
+        Some(end) => text[end + 1..].trim(),
+        None => text,
+    }
+}
+
+/// Number of leading words of `next` that repeat the end of `stitched`
+///
+/// Tries every overlap length up to `max_words` and keeps the one whose word
+/// runs are closest by edit distance, preferring longer runs on a tie. Runs
+/// differing in more than `MAX_OVERLAP_ERROR` of their words don't count.
+fn overlap_words(stitched: &[&str], next: &[&str], max_words: usize) -> usize {
+    let max_words = max_words.min(stitched.len()).min(next.len());
+    let tail: Vec<String> = stitched[stitched.len() - max_words..]
+        .iter()
+        .map(|word| normalize_word(word))
+        .collect();
+    let head: Vec<String> = next[..max_words].iter().map(|word| normalize_word(word)).collect();
+
+    let mut best: Option<(f64, usize)> = None;
+    for length in (1..=max_words).rev() {
+        let distance = edit_distance(&tail[max_words - length..], &head[..length]);
+        let error = distance as f64 / length as f64;
+        let better = match best {
+            Some((best_error, _)) => error < best_error,
+            None => true,
+        };
+        if error <= MAX_OVERLAP_ERROR && better {
+            best = Some((error, length));
+        }
+    }
+    best.map_or(0, |(_, length)| length)
+}
+
+/// Append the words of the next chunk to `merged`, keeping the words both
+/// chunks transcribed in their overlap only once
+///
+/// Times are absolute. The overlap runs from `overlap_start_ms`, where the next
+/// chunk starts, to `overlap_end_ms`, where the previous one ended. Without a
+/// shared word the chunks are cut where the preferred chunk's side of the
+/// overlap begins, so all of its overlapping words are kept.
+fn merge_overlap(
+    merged: &mut Vec<WordTimestamp>,
+    next: Vec<WordTimestamp>,
+    overlap_start_ms: i64,
+    overlap_end_ms: i64,
+    strategy: OverlapStrategy,
+) {
+    if merged.is_empty() {
+        merged.extend(next);
+        return;
+    }
+
+    let mid = |word: &WordTimestamp| (word.start_ms + word.end_ms) / 2;
+    let overlap = overlap_start_ms - ANCHOR_TOLERANCE_MS..=overlap_end_ms + ANCHOR_TOLERANCE_MS;
+    let in_overlap = |word: &WordTimestamp| overlap.contains(&mid(word));
+
+    // Pairs of the same word heard by both chunks at (nearly) the same time
+    let mut anchors = Vec::new();
+    for (i, earlier) in merged.iter().enumerate() {
+        if !in_overlap(earlier) {
+            continue;
+        }
+        let earlier_word = normalize_word(&earlier.word);
+        if earlier_word.is_empty() {
+            continue;
+        }
+        for (j, later) in next.iter().enumerate() {
+            if !in_overlap(later) {
+                break;
+            }
+            if (mid(earlier) - mid(later)).abs() <= ANCHOR_TOLERANCE_MS
+                && normalize_word(&later.word) == earlier_word
+            {
+                anchors.push((i, j));
+            }
+        }
+    }
+    let anchor = match strategy {
+        OverlapStrategy::PreferLater => anchors.first(),
+        OverlapStrategy::PreferEarlier => anchors.last(),
+    };
+
+    let (keep, skip) = match anchor {
+        Some(&(i, j)) => (i + 1, j + 1),
+        None => {
+            let seam = match strategy {
+                OverlapStrategy::PreferLater => overlap_start_ms,
+                OverlapStrategy::PreferEarlier => overlap_end_ms,
+            };
+            let after_seam = |words: &[WordTimestamp]| {
+                let position = words.iter().position(|word| mid(word) >= seam);
+                position.unwrap_or(words.len())
+            };
+            (after_seam(merged), after_seam(&next))
+        }
+    };
+    merged.truncate(keep);
+    merged.extend(next.into_iter().skip(skip));
+}
+
+/// Transcribe a long recording in overlapping chunks of `chunk_secs`
+///
+/// Each chunk is decoded separately with the previous chunk's last sentence as
+/// its initial prompt, so context carries across chunk boundaries. The words
+/// transcribed twice in the `overlap_secs` shared by neighbouring chunks are
+/// found by edit distance and kept only once. Suited to podcast-length audio
+/// where a single whole-file run drifts or repeats itself.
+///
+/// With `per_chunk_language_detection`, `language` is ignored and each chunk
+/// is decoded in the language whisper detects for it. The previous chunk's
+/// sentence isn't carried over then, since it could be in another language.
+/// With an `overlap_strategy`, the overlap is resolved by word timestamps
+/// instead (see `OverlapStrategy`).
+#[tauri::command]
+pub async fn transcribe_long_audio(
+    audio_data: Vec<u8>,
+    chunk_secs: f32,
+    overlap_secs: f32,
+    model_path: String,
+    language: Option<String>,
+    options: Option<LongAudioOptions>,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<LongAudioTranscript, TranscriptionError> {
+    if chunk_secs <= 0.0 || !(0.0..chunk_secs).contains(&overlap_secs) {
+        return Err(TranscriptionError::TranscriptionError {
+            message: format!(
+                "Invalid chunking: {}s chunks with {}s overlap; the overlap must be shorter than \
+                 the chunk",
+                chunk_secs, overlap_secs
+            ),
+        });
+    }
+
+    let LongAudioOptions {
+        per_chunk_language_detection,
+        overlap_strategy,
+    } = options.unwrap_or_default();
+    let detect_per_chunk = per_chunk_language_detection.unwrap_or(false);
+    let language = if detect_per_chunk { None } else { language };
+    let mut options = WhisperOptions {
+        threads: model_manager.inference_threads(),
+        word_timestamps: Some(overlap_strategy.is_some()),
+        ..Default::default()
+    };
+
+    let samples = whisper_samples(audio_data, &options)?;
+    if samples.is_empty() {
+        return Ok(LongAudioTranscript {
+            text: String::new(),
+            chunks: Vec::new(),
+        });
+    }
+
+    let chunk_len = ((chunk_secs * SAMPLE_RATE) as usize).max(1);
+    let overlap_len = (overlap_secs * SAMPLE_RATE) as usize;
+    let step = chunk_len.saturating_sub(overlap_len).max(1);
+    let max_overlap_words = (overlap_secs * MAX_WORDS_PER_SEC).ceil() as usize;
+
+    // Hold the engine for the whole recording rather than once per chunk
+    let transcript = with_whisper_engine(&model_manager, &model_path, |engine| {
+        let mut stitched = String::new();
+        // Used instead of `stitched` with an overlap strategy
+        let mut merged_words = Vec::new();
+        let mut chunks = Vec::new();
+        let mut start = 0;
+        let mut previous_end_ms = 0;
+
+        loop {
+            let end = (start + chunk_len).min(samples.len());
+            let output = engine.transcribe(&samples[start..end], language.as_deref(), &options)?;
+            let chunk_text = output.text.trim().to_string();
+            let (start_ms, end_ms) = (sample_ms(start), sample_ms(end));
+            chunks.push(LongAudioChunk {
+                start_ms,
+                end_ms,
+                language: output.language,
+            });
+
+            if !chunk_text.is_empty() {
+                if !detect_per_chunk {
+                    options.initial_prompt = Some(last_sentence(&chunk_text).to_string());
+                }
+
+                if let Some(strategy) = overlap_strategy {
+                    // Word times are relative to the chunk
+                    let words = output
+                        .words
+                        .into_iter()
+                        .map(|word| WordTimestamp {
+                            start_ms: word.start_ms + start_ms,
+                            end_ms: word.end_ms + start_ms,
+                            ..word
+                        })
+                        .collect();
+                    merge_overlap(
+                        &mut merged_words,
+                        words,
+                        start_ms,
+                        previous_end_ms,
+                        strategy,
+                    );
+                } else {
+                    let stitched_words: Vec<&str> = stitched.split_whitespace().collect();
+                    let chunk_words: Vec<&str> = chunk_text.split_whitespace().collect();
+                    let skip = if start == 0 {
+                        0
+                    } else {
+                        overlap_words(&stitched_words, &chunk_words, max_overlap_words)
+                    };
+                    for word in &chunk_words[skip..] {
+                        if !stitched.is_empty() {
+                            stitched.push(' ');
+                        }
+                        stitched.push_str(word);
+                    }
+                }
+            }
+            previous_end_ms = end_ms;
+
+            if end == samples.len() {
+                break;
+            }
+            start += step;
+        }
+
+        println!(
+            "[Long Audio] Transcribed {} samples in {} chunks of {}s ({}s overlap)",
+            samples.len(),
+            chunks.len(),
+            chunk_secs,
+            overlap_secs
+        );
+        if overlap_strategy.is_some() {
+            let words: Vec<&str> = merged_words.iter().map(|word| word.word.as_str()).collect();
+            stitched = words.join(" ");
+        }
+        Ok(LongAudioTranscript {
+            text: stitched,
+            chunks,
+        })
+    })?;
+
+    Ok(transcript)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRANSCRIPT: &str = "the quick brown fox jumps over the lazy dog while the farmer \
+        watches from the porch and the sun sets slowly behind the hills so the fox runs \
+        home to the den before the night falls on the quiet valley";
+
+    fn word(word: &str, start_ms: i64, end_ms: i64) -> WordTimestamp {
+        WordTimestamp {
+            word: word.to_string(),
+            start_ms,
+            end_ms,
+            probability: 1.0,
+        }
+    }
+
+    /// `TRANSCRIPT` as a single pass would time it, one word every 400 ms
+    fn single_pass() -> Vec<WordTimestamp> {
+        TRANSCRIPT
+            .split_whitespace()
+            .enumerate()
+            .map(|(k, text)| word(text, k as i64 * 400 + 150, k as i64 * 400 + 450))
+            .collect()
+    }
+
+    /// What a chunk from `start_ms` to `end_ms` would transcribe: the words
+    /// inside it, with any word cut by its edges misheard
+    fn chunk(words: &[WordTimestamp], start_ms: i64, end_ms: i64) -> Vec<WordTimestamp> {
+        words
+            .iter()
+            .filter(|spoken| spoken.end_ms > start_ms && spoken.start_ms < end_ms)
+            .map(|spoken| {
+                let cut = spoken.start_ms < start_ms || spoken.end_ms > end_ms;
+                let text = if cut { "uh" } else { spoken.word.as_str() };
+                word(text, spoken.start_ms, spoken.end_ms)
+            })
+            .collect()
+    }
+
+    /// Split the single pass into overlapping chunks and merge them back
+    fn merge_chunks(strategy: OverlapStrategy, chunk_ms: i64, overlap_ms: i64) -> String {
+        let words = single_pass();
+        let total_ms = words.last().unwrap().end_ms;
+        let mut merged = Vec::new();
+        let (mut start_ms, mut previous_end_ms) = (0, 0);
+        loop {
+            let end_ms = (start_ms + chunk_ms).min(total_ms);
+            let next = chunk(&words, start_ms, end_ms);
+            merge_overlap(&mut merged, next, start_ms, previous_end_ms, strategy);
+            if end_ms == total_ms {
+                break;
+            }
+            previous_end_ms = end_ms;
+            start_ms += chunk_ms - overlap_ms;
+        }
+        let merged: Vec<&str> = merged.iter().map(|word| word.word.as_str()).collect();
+        merged.join(" ")
+    }
+
+    #[test]
+    fn merged_chunks_match_single_pass() {
+        let expected: Vec<&str> = TRANSCRIPT.split_whitespace().collect();
+        let expected = expected.join(" ");
+        for strategy in [OverlapStrategy::PreferLater, OverlapStrategy::PreferEarlier] {
+            for (chunk_ms, overlap_ms) in [(6000, 2000), (5000, 1500), (8000, 3000)] {
+                assert_eq!(
+                    merge_chunks(strategy, chunk_ms, overlap_ms),
+                    expected,
+                    "{:?} with {} ms chunks, {} ms overlap",
+                    strategy,
+                    chunk_ms,
+                    overlap_ms
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn without_shared_words_keeps_preferred_chunk() {
+        let earlier = vec![word("a", 0, 300), word("b", 400, 700), word("c", 800, 1100)];
+        let later = vec![word("see", 800, 1100), word("d", 1200, 1500)];
+        let merge = |strategy| {
+            let mut merged = earlier.clone();
+            merge_overlap(&mut merged, later.clone(), 700, 1200, strategy);
+            let merged: Vec<String> = merged.into_iter().map(|word| word.word).collect();
+            merged
+        };
+
+        assert_eq!(merge(OverlapStrategy::PreferLater), ["a", "b", "see", "d"]);
+        assert_eq!(merge(OverlapStrategy::PreferEarlier), ["a", "b", "c", "d"]);
+    }
+}
@@ -0,0 +1,98 @@
+use super::error::TranscriptionError;
+use serde::Serialize;
+use std::path::Path;
+use sysinfo::System;
+
+/// Runtime memory a model needs relative to its size on disk
+///
+/// whisper.cpp and ONNX Runtime allocate compute and KV buffers on top of the
+/// weights, so a loaded model uses noticeably more than the file size.
+const RUNTIME_OVERHEAD: f64 = 1.5;
+
+/// Free memory we want left over after loading for a load to count as `ok`
+const COMFORT_MARGIN: f64 = 1.25;
+
+/// Whether a model is expected to fit in memory
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MemoryRecommendation {
+    /// Fits with room to spare
+    Ok,
+    /// Fits, but little memory will be left for the rest of the system
+    Tight,
+    /// Needs more memory than is currently available
+    WillLikelyFail,
+}
+
+/// Result of `can_load_model` - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelMemoryCheck {
+    pub recommendation: MemoryRecommendation,
+    pub model_bytes: u64,
+    pub estimated_required_bytes: u64,
+    pub available_bytes: u64,
+    pub total_bytes: u64,
+}
+
+/// Size of a model on disk; Parakeet models are directories of several files
+pub(crate) fn model_size(path: &Path) -> std::io::Result<u64> {
+    let metadata = std::fs::metadata(path)?;
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+
+    let mut total = 0;
+    for entry in std::fs::read_dir(path)? {
+        total += model_size(&entry?.path())?;
+    }
+    Ok(total)
+}
+
+/// Approximate memory a model uses once loaded, from its size on disk
+pub(crate) fn estimated_runtime_bytes(path: &Path) -> Option<u64> {
+    model_size(path)
+        .ok()
+        .map(|bytes| (bytes as f64 * RUNTIME_OVERHEAD) as u64)
+}
+
+/// Check whether a model is likely to fit in the currently available RAM
+///
+/// Compares the model size (times a runtime overhead factor) against available
+/// system memory, so the UI can warn before a load gets the process OOM-killed.
+#[tauri::command]
+pub async fn can_load_model(model_path: String) -> Result<ModelMemoryCheck, TranscriptionError> {
+    let model_bytes = model_size(Path::new(&model_path)).map_err(|e| {
+        TranscriptionError::ModelLoadError {
+            message: format!("Failed to read model '{}': {}", model_path, e),
+        }
+    })?;
+
+    let mut system = System::new();
+    system.refresh_memory();
+    let available_bytes = system.available_memory();
+    let total_bytes = system.total_memory();
+
+    let estimated_required_bytes = (model_bytes as f64 * RUNTIME_OVERHEAD) as u64;
+    let comfortable = estimated_required_bytes as f64 * COMFORT_MARGIN <= available_bytes as f64;
+    let recommendation = if comfortable {
+        MemoryRecommendation::Ok
+    } else if estimated_required_bytes <= available_bytes {
+        MemoryRecommendation::Tight
+    } else {
+        MemoryRecommendation::WillLikelyFail
+    };
+
+    println!(
+        "[Memory] Model {} needs ~{} bytes, {} of {} bytes available: {:?}",
+        model_path, estimated_required_bytes, available_bytes, total_bytes, recommendation
+    );
+
+    Ok(ModelMemoryCheck {
+        recommendation,
+        model_bytes,
+        estimated_required_bytes,
+        available_bytes,
+        total_bytes,
+    })
+}
@@ -1,23 +1,127 @@
+pub mod batch;
+pub mod capabilities;
+mod cancellation;
 mod error;
+pub mod export;
+pub mod ffmpeg;
+pub mod gpu;
+pub mod long_audio;
+pub mod memory;
 mod model_manager;
-
-use error::TranscriptionError;
-pub use model_manager::ModelManager;
-use std::path::PathBuf;
-#[cfg(target_os = "windows")]
-use std::os::windows::process::CommandExt;
-use std::io::Write;
+mod post_process;
+pub mod precheck;
+mod symphonia_decode;
+pub mod translate;
+pub mod url;
+mod vad;
+pub mod verify;
+mod whisper_cpp;
+
+use cancellation::CancellationToken;
+pub use cancellation::CancellationTokens;
+pub use error::TranscriptionError;
+use export::{Segment, TranscriptDocument, WordTimestamp};
+use model_manager::EngineKind;
+pub use model_manager::{LoadedModelInfo, ModelInfo, ModelManager};
+pub use whisper_cpp::WhisperOptions;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use tauri::Emitter;
+use std::io::{Read, Write};
+use std::process::{Command, Output, Stdio};
+use std::time::Duration;
 use transcribe_rs::{
     TranscriptionEngine,
-    engines::{
-        whisper::{WhisperEngine, WhisperInferenceParams},
-        parakeet::{ParakeetInferenceParams, TimestampGranularity},
-    },
+    engines::parakeet::{ParakeetInferenceParams, TimestampGranularity},
 };
 
 #[cfg(target_os = "windows")]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
-use rubato::{Resampler, SincFixedIn, SincInterpolationType, SincInterpolationParameters, WindowFunction};
+use rubato::{
+    FastFixedIn, PolynomialDegree, ResamplerConstructionError, SincFixedIn,
+    SincInterpolationType, SincInterpolationParameters, VecResampler, WindowFunction,
+};
+use serde::{Deserialize, Serialize};
+
+/// Input shorter than this is resampled with `High` quality under `Auto`
+const AUTO_HIGH_QUALITY_MAX_SECS: f64 = 60.0;
+/// Input longer than this is resampled with `Fast` quality under `Auto`
+const AUTO_FAST_QUALITY_MIN_SECS: f64 = 30.0 * 60.0;
+
+/// Resampling quality used when converting audio to 16kHz in pure Rust
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResampleQuality {
+    /// Polynomial interpolation; much faster than sinc, fine for speech
+    Fast,
+    /// Short sinc filter tuned for speech (the original behaviour)
+    Balanced,
+    /// Long sinc filter with cubic interpolation
+    High,
+    /// Pick by input length: `High` under 1 minute, `Fast` over 30 minutes,
+    /// `Balanced` in between. Sinc resampling is cheap for short clips but
+    /// dominates conversion time on long recordings.
+    #[default]
+    Auto,
+}
+
+impl ResampleQuality {
+    /// Resolve `Auto` to a concrete quality for `num_samples` mono samples at `sample_rate`
+    pub(crate) fn resolve(self, num_samples: usize, sample_rate: u32) -> Self {
+        if self != ResampleQuality::Auto {
+            return self;
+        }
+
+        let duration_secs = num_samples as f64 / sample_rate as f64;
+        if duration_secs < AUTO_HIGH_QUALITY_MAX_SECS {
+            ResampleQuality::High
+        } else if duration_secs > AUTO_FAST_QUALITY_MIN_SECS {
+            ResampleQuality::Fast
+        } else {
+            ResampleQuality::Balanced
+        }
+    }
+}
+
+/// Create a mono resampler for the given (already resolved) quality
+pub(crate) fn build_resampler(
+    quality: ResampleQuality,
+    resample_ratio: f64,
+    chunk_size: usize,
+) -> Result<Box<dyn VecResampler<f32>>, ResamplerConstructionError> {
+    // Max relative ratio of 8.0 supports input down to 2kHz
+    let resampler: Box<dyn VecResampler<f32>> = match quality {
+        ResampleQuality::Fast => Box::new(FastFixedIn::<f32>::new(
+            resample_ratio,
+            8.0,
+            PolynomialDegree::Cubic,
+            chunk_size,
+            1, // mono
+        )?),
+        ResampleQuality::High => {
+            let params = SincInterpolationParameters {
+                sinc_len: 256,
+                f_cutoff: 0.95,
+                interpolation: SincInterpolationType::Cubic,
+                oversampling_factor: 256,
+                window: WindowFunction::BlackmanHarris2,
+            };
+            Box::new(SincFixedIn::<f32>::new(resample_ratio, 8.0, params, chunk_size, 1)?)
+        }
+        ResampleQuality::Balanced | ResampleQuality::Auto => {
+            // Optimized for speech
+            let params = SincInterpolationParameters {
+                sinc_len: 64,      // Reduced from 256 for better performance (adequate for speech)
+                f_cutoff: 0.95,    // Keep high to preserve speech frequencies
+                interpolation: SincInterpolationType::Linear,
+                oversampling_factor: 128,  // Reduced from 256 (still good quality)
+                window: WindowFunction::BlackmanHarris2,
+            };
+            Box::new(SincFixedIn::<f32>::new(resample_ratio, 8.0, params, chunk_size, 1)?)
+        }
+    };
+    Ok(resampler)
+}
 
 /// Check if audio is already in whisper-compatible format (16kHz, mono, 16-bit PCM)
 fn is_valid_wav_format(audio_data: &[u8]) -> bool {
@@ -34,27 +138,63 @@ fn is_valid_wav_format(audio_data: &[u8]) -> bool {
     }
 }
 
+/// Read as many complete samples as the file actually contains
+///
+/// Recordings interrupted by a crash can have a data chunk that is shorter than
+/// the header declares (or still holds placeholder sizes). Instead of failing on
+/// the first read past the end, keep everything that was readable and log the
+/// mismatch. Only fails if not a single sample could be read.
+fn read_available_samples<R: std::io::Read, S: hound::Sample>(
+    reader: &mut hound::WavReader<R>,
+    label: &str,
+) -> Result<Vec<S>, TranscriptionError> {
+    let declared = reader.len();
+    let mut samples = Vec::new();
+
+    for sample in reader.samples::<S>() {
+        match sample {
+            Ok(sample) => samples.push(sample),
+            Err(e) if !samples.is_empty() => {
+                eprintln!(
+                    "[Read Samples] WARNING: header declares {} {} samples but only {} could be read ({}), using the readable audio",
+                    declared, label, samples.len(), e
+                );
+                break;
+            }
+            Err(e) => {
+                eprintln!("[Read Samples] Failed to read {} samples: {}", label, e);
+                let message = format!("Failed to read {} samples: {}", label, e);
+                return Err(TranscriptionError::audio_read_from(message, e));
+            }
+        }
+    }
+
+    Ok(samples)
+}
+
 /// Convert audio to whisper-compatible format using pure Rust (no FFmpeg required)
 ///
 /// This function converts audio from various formats to 16kHz mono 16-bit PCM WAV.
 /// It handles:
 /// - Channel conversion: stereo → mono (by averaging channels)
 /// - Sample format conversion: any format → f32 → 16-bit PCM
-/// - Sample rate conversion: any Hz → 16kHz using high-quality resampling
+/// - Sample rate conversion: any Hz → 16kHz, at the requested `ResampleQuality`
 ///
 /// This is used as a fallback when FFmpeg is not available, and can handle
-/// most uncompressed WAV formats. For compressed formats (MP3, M4A, etc.),
-/// FFmpeg is still required.
-fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError> {
+/// most uncompressed WAV formats. Compressed formats (MP3, M4A, etc.) are
+/// decoded by the symphonia tier or FFmpeg instead.
+fn convert_audio_rust(
+    audio_data: Vec<u8>,
+    resample_quality: ResampleQuality,
+    cancel: Option<&CancellationToken>,
+) -> Result<Vec<u8>, TranscriptionError> {
     println!("[Rust Audio Conversion] Starting conversion of {} bytes", audio_data.len());
 
     // Read the input WAV file
     let cursor = std::io::Cursor::new(&audio_data);
     let mut reader = hound::WavReader::new(cursor).map_err(|e| {
         eprintln!("[Rust Audio Conversion] Failed to parse WAV file: {}", e);
-        TranscriptionError::AudioReadError {
-            message: format!("Failed to parse WAV file: {}", e),
-        }
+        TranscriptionError::audio_read_from(format!("Failed to parse WAV file: {}", e), e)
     })?;
 
     let spec = reader.spec();
@@ -70,44 +210,55 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
             match spec.bits_per_sample {
                 16 => {
                     // 16-bit PCM: divide by 32768.0 to normalize
-                    reader
-                        .samples::<i16>()
-                        .map(|s| s.map(|sample| sample as f32 / 32768.0))
-                        .collect::<Result<Vec<_>, _>>()
-                        .map_err(|e| TranscriptionError::AudioReadError {
-                            message: format!("Failed to read 16-bit samples: {}", e),
-                        })?
+                    read_available_samples::<_, i16>(&mut reader, "16-bit")?
+                        .into_iter()
+                        .map(|sample| sample as f32 / 32768.0)
+                        .collect()
+                }
+                24 => {
+                    // 24-bit PCM: hound sign-extends into i32, divide by 8388608.0 (2^23)
+                    read_available_samples::<_, i32>(&mut reader, "24-bit")?
+                        .into_iter()
+                        .map(|sample| sample as f32 / 8388608.0)
+                        .collect()
                 }
                 32 => {
                     // 32-bit PCM: divide by 2147483648.0 to normalize
-                    reader
-                        .samples::<i32>()
-                        .map(|s| s.map(|sample| sample as f32 / 2147483648.0))
-                        .collect::<Result<Vec<_>, _>>()
-                        .map_err(|e| TranscriptionError::AudioReadError {
-                            message: format!("Failed to read 32-bit samples: {}", e),
-                        })?
+                    read_available_samples::<_, i32>(&mut reader, "32-bit")?
+                        .into_iter()
+                        .map(|sample| sample as f32 / 2147483648.0)
+                        .collect()
                 }
                 _ => {
-                    return Err(TranscriptionError::AudioReadError {
-                        message: format!("Unsupported bit depth: {} bits", spec.bits_per_sample),
-                    });
+                    return Err(TranscriptionError::audio_read(format!(
+                        "Unsupported bit depth: {} bits",
+                        spec.bits_per_sample
+                    )));
                 }
             }
         }
         hound::SampleFormat::Float => {
             // 32-bit float: already in [-1.0, 1.0] range
-            reader
-                .samples::<f32>()
-                .collect::<Result<Vec<_>, _>>()
-                .map_err(|e| TranscriptionError::AudioReadError {
-                    message: format!("Failed to read float samples: {}", e),
-                })?
+            read_available_samples::<_, f32>(&mut reader, "float")?
         }
     };
 
     println!("[Rust Audio Conversion] Read {} samples", samples_f32.len());
 
+    encode_for_whisper(samples_f32, sample_rate, channels, resample_quality, cancel)
+}
+
+/// Mix interleaved f32 samples down to mono, resample them to 16kHz and encode
+/// them as a 16-bit PCM WAV, the format whisper requires
+///
+/// Shared by the pure Rust conversion tiers, whatever they decoded from.
+fn encode_for_whisper(
+    samples_f32: Vec<f32>,
+    sample_rate: u32,
+    channels: usize,
+    resample_quality: ResampleQuality,
+    cancel: Option<&CancellationToken>,
+) -> Result<Vec<u8>, TranscriptionError> {
     // Step 2: Convert channels to mono (if needed)
     let mono_samples: Vec<f32> = if channels == 1 {
         // Already mono, use as-is
@@ -143,33 +294,20 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
 
         // Validate sample rate (support down to 2kHz)
         if resample_ratio > 8.0 {
-            return Err(TranscriptionError::AudioReadError {
-                message: format!("Sample rate {} Hz is too low (minimum 2000 Hz)", sample_rate),
-            });
+            return Err(TranscriptionError::audio_read(format!(
+                "Sample rate {} Hz is too low (minimum 2000 Hz)",
+                sample_rate
+            )));
         }
 
-        // Calculate resampling parameters (optimized for speech)
         let chunk_size = 1024; // Process in chunks for efficiency
-        let params = SincInterpolationParameters {
-            sinc_len: 64,      // Reduced from 256 for better performance (adequate for speech)
-            f_cutoff: 0.95,    // Keep high to preserve speech frequencies
-            interpolation: SincInterpolationType::Linear,
-            oversampling_factor: 128,  // Reduced from 256 (still good quality)
-            window: WindowFunction::BlackmanHarris2,
-        };
+        let quality = resample_quality.resolve(mono_samples.len(), sample_rate);
+        println!("[Rust Audio Conversion] Resample quality: {:?} (requested {:?})", quality, resample_quality);
 
         // Create resampler (1 channel, fixed input rate)
-        let mut resampler = SincFixedIn::<f32>::new(
-            resample_ratio,
-            8.0,  // Increased from 2.0 to support down to 2kHz input
-            params,
-            chunk_size,
-            1, // mono
-        ).map_err(|e| {
+        let mut resampler = build_resampler(quality, resample_ratio, chunk_size).map_err(|e| {
             eprintln!("[Rust Audio Conversion] Failed to create resampler: {}", e);
-            TranscriptionError::AudioReadError {
-                message: format!("Failed to create resampler: {}", e),
-            }
+            TranscriptionError::audio_read_from(format!("Failed to create resampler: {}", e), e)
         })?;
 
         // Process audio in chunks since SincFixedIn expects fixed-size chunks
@@ -180,6 +318,13 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
         println!("[Rust Audio Conversion] Processing in chunks of {} samples", chunk_size);
 
         while input_pos < mono_samples.len() {
+            if cancel.is_some_and(|token| token.is_cancelled()) {
+                println!("[Rust Audio Conversion] Cancelled at position {}", input_pos);
+                return Err(TranscriptionError::Cancelled {
+                    message: "Audio conversion was cancelled".to_string(),
+                });
+            }
+
             // Get the next chunk (pad with zeros if needed for the last chunk)
             let end_pos = (input_pos + chunk_size).min(mono_samples.len());
             let mut chunk: Vec<f32> = mono_samples[input_pos..end_pos].to_vec();
@@ -195,9 +340,7 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
             // Resample this chunk
             let waves_out = resampler.process(&waves_in, None).map_err(|e| {
                 eprintln!("[Rust Audio Conversion] Resampling failed at position {}: {}", input_pos, e);
-                TranscriptionError::AudioReadError {
-                    message: format!("Resampling failed: {}", e),
-                }
+                TranscriptionError::audio_read_from(format!("Resampling failed: {}", e), e)
             })?;
 
             // Append the resampled chunk to output
@@ -242,24 +385,18 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
         };
 
         let mut writer = hound::WavWriter::new(&mut cursor, spec).map_err(|e| {
-            TranscriptionError::AudioReadError {
-                message: format!("Failed to create WAV writer: {}", e),
-            }
+            TranscriptionError::audio_read_from(format!("Failed to create WAV writer: {}", e), e)
         })?;
 
         for sample in pcm_samples {
             writer.write_sample(sample).map_err(|e| {
-                TranscriptionError::AudioReadError {
-                    message: format!("Failed to write sample: {}", e),
-                }
+                TranscriptionError::audio_read_from(format!("Failed to write sample: {}", e), e)
             })?;
         }
 
         writer.finalize().map_err(|e| {
             eprintln!("[Rust Audio Conversion] Failed to finalize WAV: {}", e);
-            TranscriptionError::AudioReadError {
-                message: format!("Failed to finalize WAV: {}", e),
-            }
+            TranscriptionError::audio_read_from(format!("Failed to finalize WAV: {}", e), e)
         })?;
     }
 
@@ -268,6 +405,67 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
     Ok(output_bytes)
 }
 
+/// Check for a RIFF/WAVE header, regardless of the sample format inside
+fn is_wav_container(audio_data: &[u8]) -> bool {
+    audio_data.len() >= 12 && &audio_data[0..4] == b"RIFF" && &audio_data[8..12] == b"WAVE"
+}
+
+/// Whether FFmpeg's stderr says the input format or codec itself is unsupported
+/// (as opposed to an I/O or other transient failure)
+fn ffmpeg_rejected_format(stderr: &str) -> bool {
+    const MARKERS: [&str; 4] = [
+        "Invalid data found when processing input",
+        "Unknown input format",
+        "could not find codec parameters",
+        "Decoder not found",
+    ];
+    MARKERS.iter().any(|marker| stderr.contains(marker))
+}
+
+/// Run FFmpeg to completion, killing it early if `cancel` fires
+///
+/// Returns `Ok(None)` when cancelled. Without a token this is just `output()`.
+fn run_ffmpeg(cmd: &mut Command, cancel: Option<&CancellationToken>) -> std::io::Result<Option<Output>> {
+    let Some(cancel) = cancel else {
+        return cmd.output().map(Some);
+    };
+
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain stderr on its own thread so FFmpeg never blocks on a full pipe
+    let mut stderr_pipe = child.stderr.take();
+    let stderr_reader = std::thread::spawn(move || {
+        let mut buf = Vec::new();
+        if let Some(pipe) = stderr_pipe.as_mut() {
+            let _ = pipe.read_to_end(&mut buf);
+        }
+        buf
+    });
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if cancel.is_cancelled() {
+            println!("[Audio Conversion] Cancelled, killing FFmpeg");
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+
+    Ok(Some(Output {
+        status,
+        stdout: Vec::new(),
+        stderr: stderr_reader.join().unwrap_or_default(),
+    }))
+}
+
 /// Convert audio to whisper-compatible format (16kHz mono PCM WAV)
 ///
 /// Whisper models require audio in a specific format:
@@ -285,17 +483,31 @@ fn convert_audio_rust(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError
 /// **Tier 2: Pure Rust Conversion (Fallback)**
 /// - Attempts to convert audio using pure Rust libraries (no external dependencies)
 /// - Handles uncompressed WAV files with various sample rates, channels, and bit depths
-/// - Uses high-quality resampling (SincFixedIn) for sample rate conversion
+/// - Resamples at `resample_quality` (`Auto` picks based on input length)
 /// - Works without FFmpeg installed, making it portable and reliable
 ///
+/// **Tier 2.5: Symphonia Decoding (Compressed Formats)**
+/// - Detects MP3, OGG Vorbis, FLAC, AAC/M4A and similar containers from their
+///   magic bytes and decodes them with symphonia, still without FFmpeg
+/// - Feeds the decoded PCM through the same mono mixdown and resampling as Tier 2
+///
 /// **Tier 3: FFmpeg Conversion (Last Resort)**
-/// - Falls back to FFmpeg for complex formats (MP3, M4A, OGG, etc.)
+/// - Falls back to FFmpeg for anything the Rust tiers can't decode
 /// - Provides comprehensive format support but requires FFmpeg installation
 /// - Returns `FfmpegNotFoundError` if FFmpeg is not available
+/// - Returns `AudioFormatNotSupported` if the input can't be handled at all
+///   (FFmpeg rejects it, or FFmpeg is missing and the input isn't WAV)
 ///
 /// This approach ensures maximum compatibility: users without FFmpeg can still
 /// transcribe most recordings, while complex formats are handled when FFmpeg is available.
-fn convert_audio_for_whisper(audio_data: Vec<u8>) -> Result<Vec<u8>, TranscriptionError> {
+///
+/// When `cancel` is given, the Rust resampling loop checks it between chunks and
+/// FFmpeg is killed as soon as it fires; either way `Cancelled` is returned.
+fn convert_audio_for_whisper(
+    audio_data: Vec<u8>,
+    resample_quality: ResampleQuality,
+    cancel: Option<&CancellationToken>,
+) -> Result<Vec<u8>, TranscriptionError> {
     println!("[Audio Conversion] Starting 3-tier conversion strategy for {} bytes", audio_data.len());
 
     // Tier 1: Skip conversion if already in correct format (fast path)
@@ -307,43 +519,68 @@ fn convert_audio_for_whisper(audio_data: Vec<u8>) -> Result<Vec<u8>, Transcripti
     println!("[Audio Conversion] Tier 1: Audio needs conversion, trying Tier 2 (pure Rust)");
 
     // Tier 2: Try pure Rust conversion (no FFmpeg required)
-    match convert_audio_rust(audio_data.clone()) {
+    match convert_audio_rust(audio_data.clone(), resample_quality, cancel) {
         Ok(converted) => {
             // Rust conversion succeeded
             println!("[Audio Conversion] Tier 2: Pure Rust conversion succeeded");
             return Ok(converted);
         }
+        Err(e @ TranscriptionError::Cancelled { .. }) => return Err(e),
         Err(e) => {
-            // Log the error but continue to FFmpeg fallback
-            eprintln!("[Audio Conversion] Tier 2: Pure Rust audio conversion failed: {}, falling back to Tier 3 (FFmpeg)", e);
+            // Log the error but continue to the compressed-format decoder
+            eprintln!("[Audio Conversion] Tier 2: Pure Rust audio conversion failed: {}, trying Tier 2.5 (Symphonia)", e);
         }
     }
 
-    // Tier 3: Fall back to FFmpeg for complex formats (MP3, M4A, OGG, etc.)
+    // Tier 2.5: Decode compressed formats (MP3, OGG, FLAC, AAC, etc.) in Rust
+    if let Some(extension) = symphonia_decode::sniff_extension(&audio_data) {
+        let decoded = symphonia_decode::decode(audio_data.clone(), extension, cancel);
+        let converted = decoded.and_then(|audio| {
+            encode_for_whisper(
+                audio.samples,
+                audio.sample_rate,
+                audio.channels,
+                resample_quality,
+                cancel,
+            )
+        });
+        match converted {
+            Ok(converted) => {
+                println!("[Audio Conversion] Tier 2.5: Symphonia decoded {} audio", extension);
+                return Ok(converted);
+            }
+            Err(e @ TranscriptionError::Cancelled { .. }) => return Err(e),
+            Err(e) => {
+                eprintln!("[Audio Conversion] Tier 2.5: Symphonia decoding failed: {}, falling back to Tier 3 (FFmpeg)", e);
+            }
+        }
+    } else {
+        println!("[Audio Conversion] Tier 2.5: Format not recognized, falling back to Tier 3 (FFmpeg)");
+    }
+
+    // Tier 3: Fall back to FFmpeg for anything the Rust tiers couldn't decode
     // Create temp files for conversion
     let mut input_file = tempfile::Builder::new()
         .suffix(".audio")
         .tempfile()
-        .map_err(|e| TranscriptionError::AudioReadError {
-            message: format!("Failed to create temp file: {}", e),
+        .map_err(|e| {
+            TranscriptionError::audio_read_from(format!("Failed to create temp file: {}", e), e)
         })?;
 
     input_file.write_all(&audio_data).map_err(|e| {
-        TranscriptionError::AudioReadError {
-            message: format!("Failed to write audio data: {}", e),
-        }
+        TranscriptionError::audio_read_from(format!("Failed to write audio data: {}", e), e)
     })?;
 
     let output_file = tempfile::Builder::new()
         .suffix(".wav")
         .tempfile()
-        .map_err(|e| TranscriptionError::AudioReadError {
-            message: format!("Failed to create output file: {}", e),
+        .map_err(|e| {
+            TranscriptionError::audio_read_from(format!("Failed to create output file: {}", e), e)
         })?;
 
     // Use FFmpeg to convert to whisper-compatible format
     let output = {
-        let mut cmd = std::process::Command::new("ffmpeg");
+        let mut cmd = ffmpeg::ffmpeg_command();
         cmd.args(&[
             "-i", &input_file.path().to_string_lossy(),
             "-ar", "16000",        // 16kHz sample rate
@@ -352,35 +589,43 @@ fn convert_audio_for_whisper(audio_data: Vec<u8>) -> Result<Vec<u8>, Transcripti
             "-y",                  // Overwrite output
             &output_file.path().to_string_lossy(),
         ]);
-        #[cfg(target_os = "windows")]
-        {
-            cmd.creation_flags(CREATE_NO_WINDOW);
-        }
-        cmd.output()
+        run_ffmpeg(&mut cmd, cancel)
     }
         .map_err(|e| {
             // Check if error is specifically "command not found"
             if e.kind() == std::io::ErrorKind::NotFound {
+                if !is_wav_container(&audio_data) {
+                    // Without FFmpeg only WAV can be converted, so this input is a dead end
+                    return TranscriptionError::AudioFormatNotSupported {
+                        message: "This audio format needs FFmpeg to convert, and FFmpeg is not installed. Install FFmpeg, or record in WAV format (e.g. using voice-activated mode) to transcribe without it.".to_string(),
+                    };
+                }
                 TranscriptionError::FfmpegNotFoundError {
                     message: "FFmpeg is not installed. Install FFmpeg to convert audio formats for local transcription.".to_string(),
                 }
             } else {
-                TranscriptionError::AudioReadError {
-                    message: format!("Failed to run ffmpeg: {}", e),
-                }
+                TranscriptionError::audio_read_from(format!("Failed to run ffmpeg: {}", e), e)
             }
+        })?
+        .ok_or_else(|| TranscriptionError::Cancelled {
+            message: "Audio conversion was cancelled".to_string(),
         })?;
 
     if !output.status.success() {
-        return Err(TranscriptionError::AudioReadError {
-            message: format!("FFmpeg conversion failed: {}", String::from_utf8_lossy(&output.stderr)),
-        });
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if ffmpeg_rejected_format(&stderr) {
+            return Err(TranscriptionError::AudioFormatNotSupported {
+                message: format!(
+                    "FFmpeg could not decode this audio. Try recording in WAV format (e.g. using voice-activated mode). Details: {}",
+                    stderr.trim()
+                ),
+            });
+        }
+        return Err(TranscriptionError::audio_read(format!("FFmpeg conversion failed: {}", stderr)));
     }
 
     std::fs::read(output_file.path()).map_err(|e| {
-        TranscriptionError::AudioReadError {
-            message: format!("Failed to read converted audio: {}", e),
-        }
+        TranscriptionError::audio_read_from(format!("Failed to read converted audio: {}", e), e)
     })
 }
 
@@ -391,25 +636,17 @@ fn extract_samples_from_wav(wav_data: Vec<u8>) -> Result<Vec<f32>, Transcription
     let cursor = std::io::Cursor::new(wav_data);
     let mut reader = hound::WavReader::new(cursor).map_err(|e| {
         eprintln!("[Extract Samples] Failed to parse WAV: {}", e);
-        TranscriptionError::AudioReadError {
-            message: format!("Failed to parse WAV: {}", e),
-        }
+        TranscriptionError::audio_read_from(format!("Failed to parse WAV: {}", e), e)
     })?;
 
     let spec = reader.spec();
     println!("[Extract Samples] WAV spec: {} Hz, {} channels, {} bits, {:?} format",
         spec.sample_rate, spec.channels, spec.bits_per_sample, spec.sample_format);
 
-    let samples: Vec<f32> = reader
-        .samples::<i16>()
-        .map(|s| s.map(|sample| sample as f32 / 32768.0))
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| {
-            eprintln!("[Extract Samples] Failed to read samples: {}", e);
-            TranscriptionError::AudioReadError {
-                message: format!("Failed to read samples: {}", e),
-            }
-        })?;
+    let samples: Vec<f32> = read_available_samples::<_, i16>(&mut reader, "16-bit")?
+        .into_iter()
+        .map(|sample| sample as f32 / 32768.0)
+        .collect();
 
     println!("[Extract Samples] Extracted {} samples successfully", samples.len());
 
@@ -420,78 +657,527 @@ fn extract_samples_from_wav(wav_data: Vec<u8>) -> Result<Vec<f32>, Transcription
     Ok(samples)
 }
 
+/// Event emitted for each segment as it is decoded when streaming is requested
+pub const TRANSCRIPTION_SEGMENT_EVENT: &str = "transcription-segment";
+
+/// Event emitted once `transcribe_audio_whisper_streaming` has the final text
+pub const TRANSCRIPTION_COMPLETE_EVENT: &str = "transcription-complete";
+
+/// A decoded segment and its position in the transcript - emitted to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamedSegment {
+    pub index: usize,
+    #[serde(flatten)]
+    pub segment: Segment,
+}
+
+/// Final result of a streamed transcription - emitted to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionComplete {
+    pub text: String,
+    pub segment_count: usize,
+}
+
+/// Transcribe audio with a whisper model
+///
+/// With `stream_segments`, each segment is also emitted as a
+/// `transcription-segment` event as soon as it is decoded, so long recordings
+/// can show progress before the final text is returned. Set the
+/// `highpass_cutoff_hz` option, e.g. to 80, to cut rumble under speech.
 #[tauri::command]
 pub async fn transcribe_audio_whisper(
     audio_data: Vec<u8>,
     model_path: String,
     language: Option<String>,
+    options: Option<WhisperOptions>,
+    stream_segments: Option<bool>,
     model_manager: tauri::State<'_, ModelManager>,
+    app_handle: tauri::AppHandle,
 ) -> Result<String, TranscriptionError> {
-    // Convert audio to 16kHz mono format that whisper requires
-    let wav_data = convert_audio_for_whisper(audio_data)?;
+    // Decoder options beyond the defaults are opt-in
+    let options = options.unwrap_or_default();
+
+    if !stream_segments.unwrap_or(false) {
+        return transcribe_whisper(
+            audio_data,
+            &model_path,
+            language.as_deref(),
+            options,
+            &model_manager,
+            None,
+        );
+    }
 
-    // Extract samples from WAV
-    let samples = extract_samples_from_wav(wav_data)?;
+    transcribe_whisper_emitting_segments(
+        audio_data,
+        &model_path,
+        language.as_deref(),
+        options,
+        &model_manager,
+        app_handle,
+    )
+    .map(|(text, _)| text)
+}
 
-    // Return early if audio is empty
+/// Transcribe audio with a whisper model, streaming segments as events
+///
+/// Emits a `transcription-segment` event for each segment as it is decoded,
+/// then a `transcription-complete` event with the final text. The text is also
+/// returned, so callers can await the command or listen for the events.
+#[tauri::command]
+pub async fn transcribe_audio_whisper_streaming(
+    audio_data: Vec<u8>,
+    model_path: String,
+    language: Option<String>,
+    options: Option<WhisperOptions>,
+    model_manager: tauri::State<'_, ModelManager>,
+    app_handle: tauri::AppHandle,
+) -> Result<String, TranscriptionError> {
+    let (text, segment_count) = transcribe_whisper_emitting_segments(
+        audio_data,
+        &model_path,
+        language.as_deref(),
+        options.unwrap_or_default(),
+        &model_manager,
+        app_handle.clone(),
+    )?;
+
+    let _ = app_handle.emit(
+        TRANSCRIPTION_COMPLETE_EVENT,
+        TranscriptionComplete {
+            text: text.clone(),
+            segment_count,
+        },
+    );
+    Ok(text)
+}
+
+/// Run `transcribe_whisper`, emitting each segment as it is decoded
+///
+/// Returns the final text and the number of segments emitted.
+fn transcribe_whisper_emitting_segments(
+    audio_data: Vec<u8>,
+    model_path: &str,
+    language: Option<&str>,
+    options: WhisperOptions,
+    model_manager: &ModelManager,
+    app_handle: tauri::AppHandle,
+) -> Result<(String, usize), TranscriptionError> {
+    // Forward segments to the frontend from a separate thread while decoding;
+    // it ends once the sender is dropped at the end of transcription
+    let (segment_tx, segment_rx) = mpsc::channel::<Segment>();
+    let forwarder = std::thread::spawn(move || {
+        let mut count = 0;
+        for (index, segment) in segment_rx.into_iter().enumerate() {
+            let streamed = StreamedSegment { index, segment };
+            let _ = app_handle.emit(TRANSCRIPTION_SEGMENT_EVENT, streamed);
+            count = index + 1;
+        }
+        count
+    });
+
+    let result = transcribe_whisper(
+        audio_data,
+        model_path,
+        language,
+        options,
+        model_manager,
+        Some(segment_tx),
+    );
+    let segment_count = forwarder.join().unwrap_or(0);
+    result.map(|text| (text, segment_count))
+}
+
+/// Transcript with details about the audio - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionResult {
+    pub text: String,
+    /// Language whisper decoded in, e.g. `en`; the detected one when
+    /// `language` was unset or `auto`
+    pub detected_language: Option<String>,
+    /// Duration of the transcribed audio
+    pub duration_ms: u64,
+}
+
+/// Transcribe audio with a whisper model, returning the detected language
+/// and audio duration along with the text
+///
+/// Takes the same arguments as `transcribe_audio_whisper` without streaming.
+#[tauri::command]
+pub async fn transcribe_audio_whisper_full(
+    audio_data: Vec<u8>,
+    model_path: String,
+    language: Option<String>,
+    options: Option<WhisperOptions>,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<TranscriptionResult, TranscriptionError> {
+    let (result, _) = transcribe_whisper_full(
+        audio_data,
+        &model_path,
+        language.as_deref(),
+        options.unwrap_or_default(),
+        &model_manager,
+        None,
+    )?;
+
+    println!(
+        "[Transcribe Full] {} ms of audio, language {:?}",
+        result.duration_ms, result.detected_language
+    );
+    Ok(result)
+}
+
+/// Transcribe audio with a whisper model, returning SRT subtitles
+///
+/// Takes the same arguments as `transcribe_audio_whisper` without streaming.
+/// `max_segment_len` is the natural way to size the cues; `strip_annotations`
+/// applies, while paragraph formatting and post-processing don't.
+#[tauri::command]
+pub async fn transcribe_audio_whisper_srt(
+    audio_data: Vec<u8>,
+    model_path: String,
+    language: Option<String>,
+    options: Option<WhisperOptions>,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<String, TranscriptionError> {
+    let (_, segments) = transcribe_whisper_full(
+        audio_data,
+        &model_path,
+        language.as_deref(),
+        options.unwrap_or_default(),
+        &model_manager,
+        None,
+    )?;
+    Ok(export::format_as_srt(&segments))
+}
+
+/// Transcribe audio with a whisper model, returning WebVTT subtitles
+///
+/// Same arguments and cue behaviour as `transcribe_audio_whisper_srt`.
+#[tauri::command]
+pub async fn transcribe_audio_whisper_vtt(
+    audio_data: Vec<u8>,
+    model_path: String,
+    language: Option<String>,
+    options: Option<WhisperOptions>,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<String, TranscriptionError> {
+    let (_, segments) = transcribe_whisper_full(
+        audio_data,
+        &model_path,
+        language.as_deref(),
+        options.unwrap_or_default(),
+        &model_manager,
+        None,
+    )?;
+    Ok(export::format_as_vtt(&segments))
+}
+
+/// Transcribe audio with a whisper model, returning a structured transcript
+///
+/// Same arguments as `transcribe_audio_whisper_srt`. The text has all output
+/// options applied; the segments have annotation stripping only.
+#[tauri::command]
+pub async fn transcribe_audio_whisper_json(
+    audio_data: Vec<u8>,
+    model_path: String,
+    language: Option<String>,
+    options: Option<WhisperOptions>,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<TranscriptDocument, TranscriptionError> {
+    let (result, segments) = transcribe_whisper_full(
+        audio_data,
+        &model_path,
+        language.as_deref(),
+        options.unwrap_or_default(),
+        &model_manager,
+        None,
+    )?;
+    Ok(TranscriptDocument::new(
+        result.text,
+        result.detected_language,
+        result.duration_ms,
+        segments,
+    ))
+}
+
+/// Transcribe audio with a whisper model, returning word-level timestamps
+///
+/// Enables whisper.cpp's token timestamps and merges tokens into words, each
+/// with its timing and mean token probability. Output filtering options
+/// (`strip_annotations`, paragraphs, post-processing) don't apply to words.
+#[tauri::command]
+pub async fn transcribe_audio_whisper_with_timestamps(
+    audio_data: Vec<u8>,
+    model_path: String,
+    language: Option<String>,
+    options: Option<WhisperOptions>,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<Vec<WordTimestamp>, TranscriptionError> {
+    let mut options = options.unwrap_or_default();
+    options.threads = options.threads.or_else(|| model_manager.inference_threads());
+    load_prompt_file(&mut options)?;
+    options.word_timestamps = Some(true);
+
+    let samples = whisper_samples(audio_data, &options)?;
     if samples.is_empty() {
-        return Ok(String::new());
+        return Ok(Vec::new());
     }
 
-    // Get or load the model using the persistent model manager
-    let engine_arc = model_manager
-        .get_or_load_whisper(PathBuf::from(&model_path))
-        .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
+    let output = with_whisper_engine(&model_manager, &model_path, |engine| {
+        engine.transcribe(&samples, language.as_deref(), &options)
+    })?;
 
-    // Configure inference parameters
-    let mut params = WhisperInferenceParams::default();
-    params.language = language;
-    params.print_special = false;
-    params.print_progress = false;
-    params.print_realtime = false;
-    params.print_timestamps = false;
-    params.suppress_blank = true;
-    params.suppress_non_speech_tokens = true;
-    params.no_speech_thold = 0.2;
+    println!("[Word Timestamps] {} words", output.words.len());
+    Ok(output.words)
+}
 
-    // Run transcription with the persistent engine
-    let result = {
-        let mut engine_guard = engine_arc.lock().unwrap();
-        let engine = engine_guard.as_mut().ok_or_else(|| {
-            TranscriptionError::ModelLoadError {
-                message: "Model failed to load".to_string(),
-            }
-        })?;
+/// Convert, transcribe and post-process audio with a whisper model
+///
+/// This is the transcription entry point for use outside of Tauri commands.
+/// When `segments` is given, each segment is sent on it as soon as it is
+/// decoded, so callers can read partial results from the matching `Receiver`
+/// on another thread while this call blocks. Streamed segments are the
+/// decoder's raw output, before `strip_annotations` and paragraph formatting.
+pub fn transcribe_whisper(
+    audio_data: Vec<u8>,
+    model_path: &str,
+    language: Option<&str>,
+    options: WhisperOptions,
+    model_manager: &ModelManager,
+    segments: Option<mpsc::Sender<Segment>>,
+) -> Result<String, TranscriptionError> {
+    transcribe_whisper_full(audio_data, model_path, language, options, model_manager, segments)
+        .map(|(result, _)| result.text)
+}
 
-        // Extract the WhisperEngine from the enum
-        let whisper_engine = match engine {
-            model_manager::Engine::Whisper(e) => e,
-            _ => return Err(TranscriptionError::ModelLoadError {
-                message: "Expected Whisper engine but got different type".to_string(),
-            }),
+/// `transcribe_whisper`, also reporting the detected language and duration
+///
+/// The timed segments are returned alongside, after annotation stripping and
+/// VAD remapping but without paragraph formatting or post-processing.
+fn transcribe_whisper_full(
+    audio_data: Vec<u8>,
+    model_path: &str,
+    language: Option<&str>,
+    mut options: WhisperOptions,
+    model_manager: &ModelManager,
+    segments: Option<mpsc::Sender<Segment>>,
+) -> Result<(TranscriptionResult, Vec<Segment>), TranscriptionError> {
+    options.threads = options.threads.or_else(|| model_manager.inference_threads());
+    load_prompt_file(&mut options)?;
+
+    let samples = whisper_samples(audio_data, &options)?;
+    // Samples are 16kHz mono
+    let duration_ms = samples.len() as u64 / 16;
+
+    // Optionally skip silent stretches; timestamps are mapped back afterwards
+    let (samples, vad_mapping) = if options.vad_filter.unwrap_or(false) {
+        let threshold_db = options
+            .vad_energy_threshold
+            .unwrap_or(vad::DEFAULT_VAD_ENERGY_THRESHOLD_DB);
+        let filtered = vad::filter_silent_frames(&samples, threshold_db);
+        println!(
+            "[VAD] Kept {} of {} samples in {} regions",
+            filtered.samples.len(),
+            samples.len(),
+            filtered.mapping.len()
+        );
+        (filtered.samples, filtered.mapping)
+    } else {
+        (samples, Vec::new())
+    };
+
+    // Return early if audio is empty
+    if samples.is_empty() {
+        let result = TranscriptionResult {
+            text: String::new(),
+            detected_language: None,
+            duration_ms,
         };
+        return Ok((result, Vec::new()));
+    }
 
-        whisper_engine
-            .transcribe_samples(samples, Some(params))
-            .map_err(|e| TranscriptionError::TranscriptionError {
-                message: e.to_string(),
-            })?
+    // Streamed segments need remapping too, so pass them through a relay
+    let (segments, relay) = match segments {
+        Some(outer) if !vad_mapping.is_empty() => {
+            let (inner_tx, inner_rx) = mpsc::channel::<Segment>();
+            let mapping = vad_mapping.clone();
+            let relay = std::thread::spawn(move || {
+                for mut segment in inner_rx {
+                    vad::remap_segment(&mut segment, &mapping);
+                    let _ = outer.send(segment);
+                }
+            });
+            (Some(inner_tx), Some(relay))
+        }
+        segments => (segments, None),
+    };
+
+    // Run transcription with the persistent engine
+    let result = with_whisper_engine(model_manager, model_path, |engine| {
+        engine.transcribe_streaming(&samples, language, &options, segments)
+    });
+    if let Some(relay) = relay {
+        let _ = relay.join();
+    }
+    let mut result = result?;
+
+    for segment in &mut result.segments {
+        vad::remap_segment(segment, &vad_mapping);
+    }
+    for word in &mut result.words {
+        vad::remap_word(word, &vad_mapping);
+    }
+
+    if options.strip_annotations.unwrap_or(false) {
+        result.text = post_process::strip_annotations(result.text.trim());
+        for segment in &mut result.segments {
+            segment.text = post_process::strip_annotations(&segment.text);
+        }
+    }
+
+    // Optionally lay the text out in paragraphs using the pauses between segments
+    let text = match options.paragraph_gap_ms {
+        Some(gap_ms) => post_process::format_paragraphs(&result.segments, gap_ms),
+        None => result.text.trim().to_string(),
+    };
+
+    let text = match options.post_process_command.as_deref() {
+        Some(command) => post_process::run_post_process_command(text, command),
+        None => text,
+    };
+
+    let transcription = TranscriptionResult {
+        text,
+        detected_language: result.language,
+        duration_ms,
+    };
+    Ok((transcription, result.segments))
+}
+
+/// Replace `initial_prompt` with the contents of `prompt_file_path`, if set
+fn load_prompt_file(options: &mut WhisperOptions) -> Result<(), TranscriptionError> {
+    let Some(path) = options.prompt_file_path.as_deref() else {
+        return Ok(());
+    };
+
+    let path = Path::new(path);
+    if !path.is_file() {
+        return Err(TranscriptionError::PromptFileReadError {
+            message: format!("Prompt file not found: {:?}", path),
+            source: None,
+        });
+    }
+    let prompt = std::fs::read_to_string(path).map_err(|e| {
+        TranscriptionError::PromptFileReadError {
+            message: format!("Failed to read prompt file {:?}: {}", path, e),
+            source: Some(e.into()),
+        }
+    })?;
+
+    println!("[Whisper] Loaded {} byte initial prompt from {:?}", prompt.len(), path);
+    options.initial_prompt = Some(prompt);
+    Ok(())
+}
+
+/// Convert audio to the 16kHz mono samples whisper requires
+fn whisper_samples(audio_data: Vec<u8>, options: &WhisperOptions) -> Result<Vec<f32>, TranscriptionError> {
+    let wav_data = convert_audio_for_whisper(audio_data, options.resample_quality.unwrap_or_default(), None)?;
+    let mut samples = extract_samples_from_wav(wav_data)?;
+    if let Some(cutoff_hz) = options.highpass_cutoff_hz {
+        highpass_filter(&mut samples, cutoff_hz, 16000);
+    }
+    Ok(samples)
+}
+
+/// Apply a second-order Butterworth high-pass filter to mono samples in place
+///
+/// The coefficients come from the bilinear transform of the analog prototype,
+/// with the cutoff prewarped so it lands exactly at `cutoff_hz`. Cutoffs that
+/// aren't between 0 and the Nyquist frequency leave the samples untouched.
+pub fn highpass_filter(samples: &mut [f32], cutoff_hz: f32, sample_rate: u32) {
+    let nyquist = sample_rate as f64 / 2.0;
+    if !(cutoff_hz > 0.0 && (cutoff_hz as f64) < nyquist) {
+        return;
+    }
+
+    let k = (std::f64::consts::PI * cutoff_hz as f64 / sample_rate as f64).tan();
+    let k2 = k * k;
+    let sqrt2 = std::f64::consts::SQRT_2;
+    let norm = 1.0 / (1.0 + sqrt2 * k + k2);
+    let b0 = norm;
+    let b1 = -2.0 * norm;
+    let b2 = norm;
+    let a1 = 2.0 * (k2 - 1.0) * norm;
+    let a2 = (1.0 - sqrt2 * k + k2) * norm;
+
+    // Transposed direct form II, with state kept in f64 for stability at low cutoffs
+    let (mut z1, mut z2) = (0.0f64, 0.0f64);
+    for sample in samples {
+        let x = *sample as f64;
+        let y = b0 * x + z1;
+        z1 = b1 * x - a1 * y + z2;
+        z2 = b2 * x - a2 * y;
+        *sample = y as f32;
+    }
+}
+
+/// Run `f` with the whisper engine for `model_path`, loading the model if needed
+fn with_whisper_engine<T>(
+    model_manager: &ModelManager,
+    model_path: &str,
+    f: impl FnOnce(&mut whisper_cpp::WhisperCppEngine) -> Result<T, String>,
+) -> Result<T, TranscriptionError> {
+    // Get or load the model using the persistent model manager
+    let engine_arc = model_manager
+        .get_or_load_whisper(PathBuf::from(model_path))
+        .map_err(TranscriptionError::model_load)?;
+
+    let mut engine_guard = engine_arc.lock().unwrap();
+    let engine = engine_guard.as_mut().ok_or_else(|| {
+        TranscriptionError::ModelLoadError {
+            message: "Model failed to load".to_string(),
+        }
+    })?;
+
+    // Extract the WhisperCppEngine from the enum
+    let whisper_engine = match engine {
+        model_manager::Engine::Whisper(e) => e,
+        _ => return Err(TranscriptionError::ModelLoadError {
+            message: "Expected Whisper engine but got different type".to_string(),
+        }),
     };
 
-    Ok(result.text.trim().to_string())
+    f(whisper_engine).map_err(TranscriptionError::transcription)
 }
 
+/// Transcribe audio with a Parakeet model
+///
+/// With `vad_filter`, 30 ms frames quieter than `vad_energy_threshold` dBFS
+/// (default -40) are dropped before inference.
 #[tauri::command]
 pub async fn transcribe_audio_parakeet(
     audio_data: Vec<u8>,
     model_path: String,
+    post_process_command: Option<String>,
+    vad_filter: Option<bool>,
+    vad_energy_threshold: Option<f32>,
     model_manager: tauri::State<'_, ModelManager>,
 ) -> Result<String, TranscriptionError> {
     // Convert audio to 16kHz mono format
-    let wav_data = convert_audio_for_whisper(audio_data)?;
+    let wav_data = convert_audio_for_whisper(audio_data, ResampleQuality::Auto, None)?;
 
     // Extract samples from WAV
-    let samples = extract_samples_from_wav(wav_data)?;
+    let mut samples = extract_samples_from_wav(wav_data)?;
+
+    // Only the text is returned, so the VAD mapping isn't needed
+    if vad_filter.unwrap_or(false) {
+        let threshold_db = vad_energy_threshold.unwrap_or(vad::DEFAULT_VAD_ENERGY_THRESHOLD_DB);
+        samples = vad::filter_silent_frames(&samples, threshold_db).samples;
+    }
 
     // Return early if audio is empty
     if samples.is_empty() {
@@ -501,7 +1187,7 @@ pub async fn transcribe_audio_parakeet(
     // Get or load the model using the persistent model manager
     let engine_arc = model_manager
         .get_or_load_parakeet(PathBuf::from(&model_path))
-        .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
+        .map_err(TranscriptionError::model_load)?;
 
     let params = ParakeetInferenceParams {
         timestamp_granularity: TimestampGranularity::Segment,
@@ -527,10 +1213,320 @@ pub async fn transcribe_audio_parakeet(
 
         parakeet_engine
             .transcribe_samples(samples, Some(params))
-            .map_err(|e| TranscriptionError::TranscriptionError {
-                message: e.to_string(),
-            })?
+            .map_err(|e| TranscriptionError::transcription(e.to_string()))?
     };
 
-    Ok(result.text.trim().to_string())
-}
\ No newline at end of file
+    let text = result.text.trim().to_string();
+    Ok(match post_process_command.as_deref() {
+        Some(command) => post_process::run_post_process_command(text, command),
+        None => text,
+    })
+}
+
+/// Switch the active model without a gap for in-flight transcriptions
+///
+/// The new model is loaded before it replaces the current one, so rapid
+/// settings changes never run a transcription against the wrong model.
+/// With `warm_inference`, a short dummy transcription is run afterwards so the
+/// user's first real dictation doesn't pay the first-inference cost.
+#[tauri::command]
+pub async fn switch_model(
+    model_path: String,
+    engine_type: String,
+    warm_inference: Option<bool>,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<(), TranscriptionError> {
+    let kind = EngineKind::parse(&engine_type)
+        .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
+
+    model_manager
+        .switch_model(PathBuf::from(&model_path), kind)
+        .map_err(TranscriptionError::model_load)?;
+
+    if warm_inference.unwrap_or(false) {
+        model_manager
+            .warm_up()
+            .map_err(|e| TranscriptionError::transcription(format!("Warmup inference failed: {}", e)))?;
+        println!("[Transcription] Warmed up {}", model_path);
+    }
+
+    Ok(())
+}
+
+/// Load a model if needed and run a dummy inference on silence
+///
+/// The first inference after a load is much slower than the rest, so the
+/// frontend can call this on startup or when the transcription panel opens to
+/// take that cost before the user records. Returns how long the warmup
+/// inference took in milliseconds.
+#[tauri::command]
+pub async fn warmup_model(
+    model_path: String,
+    engine_type: String,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<u64, TranscriptionError> {
+    let kind = EngineKind::parse(&engine_type)
+        .map_err(|e| TranscriptionError::ModelLoadError { message: e })?;
+
+    let elapsed = model_manager
+        .warm_up_model(PathBuf::from(&model_path), kind)
+        .map_err(TranscriptionError::transcription)?;
+    let elapsed_ms = elapsed.as_millis() as u64;
+
+    println!("[Transcription] Warmed up {} in {} ms", model_path, elapsed_ms);
+    Ok(elapsed_ms)
+}
+
+/// Set the number of inference threads used by all transcriptions
+///
+/// Applies whenever a transcription doesn't specify its own thread count.
+/// The value is clamped to the number of available cores; 0 restores the
+/// engine default. Returns the value that was stored.
+#[tauri::command]
+pub fn set_inference_threads(threads: usize, model_manager: tauri::State<'_, ModelManager>) -> usize {
+    let stored = model_manager.set_inference_threads(threads);
+    println!("[Transcription] Inference threads set to {} (requested {})", stored, threads);
+    stored
+}
+
+/// Get the app-wide inference thread count (0 = engine default)
+#[tauri::command]
+pub fn get_inference_threads(model_manager: tauri::State<'_, ModelManager>) -> usize {
+    model_manager.inference_threads().unwrap_or(0)
+}
+
+/// Choose whether whisper models run on the GPU
+///
+/// Loaded whisper models are kept for reuse across transcriptions; changing
+/// this unloads them so the next transcription loads its model once with the
+/// new setting. Has no effect on Parakeet models, or when whisper.cpp was
+/// built without GPU support.
+#[tauri::command]
+pub fn set_use_gpu(use_gpu: bool, model_manager: tauri::State<'_, ModelManager>) {
+    model_manager.set_use_gpu(use_gpu);
+    println!("[Transcription] GPU inference {}", if use_gpu { "enabled" } else { "disabled" });
+}
+
+/// Whether whisper models are loaded for GPU inference
+#[tauri::command]
+pub fn get_use_gpu(model_manager: tauri::State<'_, ModelManager>) -> bool {
+    model_manager.use_gpu()
+}
+
+/// Set how long a model may sit unused before it is unloaded (seconds)
+#[tauri::command]
+pub fn set_model_idle_timeout_secs(secs: u64, model_manager: tauri::State<'_, ModelManager>) {
+    model_manager.set_idle_timeout(Duration::from_secs(secs));
+    println!("[Transcription] Model idle timeout set to {}s", secs);
+}
+
+/// Get how long a model may sit unused before it is unloaded (seconds)
+#[tauri::command]
+pub fn get_model_idle_timeout_secs(model_manager: tauri::State<'_, ModelManager>) -> u64 {
+    model_manager.idle_timeout().as_secs()
+}
+
+/// Unload the model now if it has been idle past the timeout
+///
+/// Lets the frontend free memory on demand, e.g. when the user leaves the
+/// transcription view. Returns whether a model was unloaded.
+#[tauri::command]
+pub fn trigger_idle_check(model_manager: tauri::State<'_, ModelManager>) -> bool {
+    let unloaded = model_manager.unload_if_idle();
+    if unloaded {
+        println!("[Transcription] Unloaded idle model");
+    }
+    unloaded
+}
+
+/// Set how many models are kept loaded at once (at least one)
+///
+/// Keeping two lets users switch between a fast and an accurate model without
+/// reloading. Lowering it unloads the least recently used models. Returns the
+/// value that was stored.
+#[tauri::command]
+pub fn set_model_cache_capacity(capacity: usize, model_manager: tauri::State<'_, ModelManager>) -> usize {
+    let stored = model_manager.set_capacity(capacity);
+    println!("[Transcription] Model cache capacity set to {}", stored);
+    stored
+}
+
+/// Get how many models are kept loaded at once
+#[tauri::command]
+pub fn get_model_cache_capacity(model_manager: tauri::State<'_, ModelManager>) -> usize {
+    model_manager.capacity()
+}
+
+/// List the models currently loaded in memory, with approximate memory use
+#[tauri::command]
+pub fn get_model_cache_state(model_manager: tauri::State<'_, ModelManager>) -> Vec<LoadedModelInfo> {
+    model_manager.loaded_models()
+}
+
+/// Describe the most recently used model, or `None` if no model is loaded
+#[tauri::command]
+pub fn get_loaded_model_info(model_manager: tauri::State<'_, ModelManager>) -> Option<ModelInfo> {
+    model_manager.loaded_model_info()
+}
+
+/// Unload a specific model to free its memory
+///
+/// Returns false if that model wasn't loaded. It will be loaded again on the
+/// next transcription that asks for it.
+#[tauri::command]
+pub fn evict_model(model_path: String, model_manager: tauri::State<'_, ModelManager>) -> bool {
+    let evicted = model_manager.evict(Path::new(&model_path));
+    println!(
+        "[Transcription] Evict {}: {}",
+        model_path,
+        if evicted { "unloaded" } else { "not loaded" }
+    );
+    evicted
+}
+
+/// Unload every loaded model to free its memory, e.g. before the user opens
+/// another memory-hungry application
+///
+/// Returns the models that were unloaded, empty if none was loaded.
+/// Transcriptions already running finish first. Models are loaded again on
+/// the next transcription that asks for them.
+#[tauri::command]
+pub fn unload_model(model_manager: tauri::State<'_, ModelManager>) -> Vec<LoadedModelInfo> {
+    let unloaded = model_manager.unload_model();
+    for model in &unloaded {
+        println!("[Transcription] Unloaded {} model {}", model.engine_type, model.path);
+    }
+    unloaded
+}
+
+/// Whether any model is loaded, without touching the models themselves
+#[tauri::command]
+pub fn is_model_loaded(model_manager: tauri::State<'_, ModelManager>) -> bool {
+    model_manager.is_loaded()
+}
+
+/// Convert audio to 16kHz mono 16-bit WAV, abortable via `cancel_conversion`
+///
+/// Uses the same three-tier conversion as transcription. `token_id` is chosen
+/// by the caller and passed to `cancel_conversion` to abort; a cancelled
+/// conversion returns `Cancelled`.
+#[tauri::command]
+pub async fn convert_with_cancellation(
+    audio_data: Vec<u8>,
+    token_id: String,
+    resample_quality: Option<ResampleQuality>,
+    tokens: tauri::State<'_, CancellationTokens>,
+) -> Result<Vec<u8>, TranscriptionError> {
+    let token = tokens.register(&token_id);
+    let result =
+        convert_audio_for_whisper(audio_data, resample_quality.unwrap_or_default(), Some(&token));
+    tokens.remove(&token_id);
+    result
+}
+
+/// Convert an audio file to raw 16kHz mono signed 16-bit little-endian PCM
+///
+/// Same conversion as transcription, but returned without a WAV header for
+/// tools that expect headerless PCM (streaming ASR services, custom pipelines).
+#[tauri::command]
+pub async fn export_raw_pcm(input_path: String) -> Result<Vec<u8>, TranscriptionError> {
+    let audio_data = std::fs::read(&input_path).map_err(|e| {
+        TranscriptionError::audio_read_from(format!("Failed to read {}: {}", input_path, e), e)
+    })?;
+
+    let wav_data = convert_audio_for_whisper(audio_data, ResampleQuality::Auto, None)?;
+    let mut reader = hound::WavReader::new(std::io::Cursor::new(wav_data)).map_err(|e| {
+        TranscriptionError::audio_read_from(format!("Failed to parse converted WAV: {}", e), e)
+    })?;
+
+    let pcm: Vec<u8> = read_available_samples::<_, i16>(&mut reader, "16-bit")?
+        .into_iter()
+        .flat_map(|sample| sample.to_le_bytes())
+        .collect();
+
+    println!("[Audio Conversion] Exported {} bytes of raw PCM from {}", pcm.len(), input_path);
+    Ok(pcm)
+}
+
+/// Cancel a conversion started with `convert_with_cancellation`
+///
+/// Returns false if no conversion with that ID is running.
+#[tauri::command]
+pub fn cancel_conversion(token_id: String, tokens: tauri::State<'_, CancellationTokens>) -> bool {
+    let cancelled = tokens.cancel(&token_id);
+    println!(
+        "[Audio Conversion] Cancel requested for {}: {}",
+        token_id,
+        if cancelled { "signalled" } else { "not running" }
+    );
+    cancelled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Read a 16 kHz mono 16-bit WAV produced by the conversion
+    fn read_whisper_wav(wav: &[u8]) -> Vec<i16> {
+        let mut reader = hound::WavReader::new(std::io::Cursor::new(wav)).unwrap();
+        let spec = reader.spec();
+        assert_eq!(spec.channels, 1);
+        assert_eq!(spec.sample_rate, 16000);
+        assert_eq!(spec.bits_per_sample, 16);
+        assert_eq!(spec.sample_format, hound::SampleFormat::Int);
+        reader.samples::<i16>().map(|s| s.unwrap()).collect()
+    }
+
+    #[test]
+    fn converts_24_bit_pcm() {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 16000,
+            bits_per_sample: 24,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let input = [0, 4_194_304, -4_194_304, 8_388_607, -8_388_608, 1_048_576];
+        let mut cursor = std::io::Cursor::new(Vec::new());
+        {
+            let mut writer = hound::WavWriter::new(&mut cursor, spec).unwrap();
+            for sample in input {
+                writer.write_sample(sample).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+
+        let output =
+            convert_audio_rust(cursor.into_inner(), ResampleQuality::default(), None).unwrap();
+        assert!(is_valid_wav_format(&output));
+        // 2^23 is full scale; the output scales that to 32767
+        assert_eq!(
+            read_whisper_wav(&output),
+            vec![0, 16383, -16383, 32766, -32767, 4095]
+        );
+    }
+
+    /// RMS of a sine at `freq_hz` after `highpass_filter`, relative to its
+    /// input level, in dB. The first half is skipped so the filter has settled.
+    fn highpass_gain_db(freq_hz: f32, cutoff_hz: f32) -> f32 {
+        let sample_rate = 16000;
+        let step = 2.0 * std::f32::consts::PI * freq_hz / sample_rate as f32;
+        let tone = |n: usize| (step * n as f32).sin();
+        let mut samples: Vec<f32> = (0..2 * sample_rate as usize).map(tone).collect();
+        highpass_filter(&mut samples, cutoff_hz, sample_rate);
+
+        let settled = &samples[samples.len() / 2..];
+        let rms = (settled.iter().map(|s| s * s).sum::<f32>() / settled.len() as f32).sqrt();
+        20.0 * (rms / std::f32::consts::FRAC_1_SQRT_2).log10()
+    }
+
+    #[test]
+    fn highpass_attenuates_rumble() {
+        // A second-order filter falls 12 dB per octave, so 20 dB at 40 Hz needs
+        // a cutoff nearly two octaves above it; the typical 80 Hz gives 12 dB
+        let gain = highpass_gain_db(40.0, 150.0);
+        assert!(gain <= -20.0, "40 Hz gain {} dB", gain);
+        // Speech passes through
+        let gain = highpass_gain_db(1000.0, 150.0);
+        assert!(gain.abs() < 0.1, "1 kHz gain {} dB", gain);
+    }
+}
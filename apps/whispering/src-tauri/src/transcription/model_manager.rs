@@ -1,14 +1,27 @@
-use std::path::PathBuf;
+use super::memory::{estimated_runtime_bytes, model_size};
+use super::whisper_cpp::{WhisperCppEngine, WhisperOptions};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use transcribe_rs::engines::parakeet::{ParakeetEngine, ParakeetModelParams};
-use transcribe_rs::engines::whisper::WhisperEngine;
 use transcribe_rs::TranscriptionEngine;
+use whisper_rs::WhisperContextParameters;
+
+/// Number of models kept loaded by default, e.g. a fast and an accurate one
+const DEFAULT_CACHE_CAPACITY: usize = 2;
+
+/// How long a model may sit unused before `unload_if_idle` frees it
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Length of the silent clip used to warm up an engine (0.5s at 16kHz)
+const WARMUP_SAMPLES: usize = 8_000;
 
 /// Engine type for managing different transcription engines
 pub enum Engine {
     Parakeet(ParakeetEngine),
-    Whisper(WhisperEngine),
+    Whisper(WhisperCppEngine),
 }
 
 impl Engine {
@@ -18,125 +31,450 @@ impl Engine {
             Engine::Whisper(e) => e.unload_model(),
         }
     }
+
+    /// Run a throwaway inference on silence so the first real one doesn't pay
+    /// for buffer allocation and caching
+    fn warm_up(&mut self) -> Result<(), String> {
+        let silence = vec![0.0f32; WARMUP_SAMPLES];
+        match self {
+            Engine::Parakeet(e) => e
+                .transcribe_samples(silence, None)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            // Fix the language so warmup doesn't also run language detection
+            Engine::Whisper(e) => e
+                .transcribe(&silence, Some("en"), &WhisperOptions::default())
+                .map(|_| ()),
+        }
+    }
 }
 
-pub struct ModelManager {
+/// Which kind of engine a model is loaded into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EngineKind {
+    Parakeet,
+    Whisper,
+}
+
+impl EngineKind {
+    pub fn parse(engine_type: &str) -> Result<Self, String> {
+        match engine_type.to_lowercase().as_str() {
+            "parakeet" => Ok(EngineKind::Parakeet),
+            "whisper" => Ok(EngineKind::Whisper),
+            other => Err(format!("Unknown engine type: {}", other)),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EngineKind::Parakeet => "parakeet",
+            EngineKind::Whisper => "whisper",
+        }
+    }
+}
+
+/// A model currently held in memory - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoadedModelInfo {
+    pub path: String,
+    pub engine_type: String,
+    /// Unix time (ms) the model was last used for a transcription or load
+    pub last_used_ms: u64,
+    /// Estimated from the model's size on disk; `None` if it can't be read
+    pub approximate_memory_bytes: Option<u64>,
+}
+
+/// The most recently used model - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelInfo {
+    pub path: String,
+    pub engine_type: String,
+    /// Size of the model on disk, read when it was loaded
+    pub size_bytes: u64,
+    /// How long loading the model took
+    pub load_time_ms: u64,
+}
+
+/// A loaded model in the cache
+///
+/// Each loaded model gets its own `Arc`, so callers that already hold an engine
+/// keep using it even if the model is evicted from the cache underneath them.
+struct CachedModel {
+    path: PathBuf,
+    kind: EngineKind,
+    /// Whisper models only; a different setting needs a new context
+    use_gpu: bool,
     engine: Arc<Mutex<Option<Engine>>>,
-    current_model_path: Arc<Mutex<Option<PathBuf>>>,
-    last_activity: Arc<Mutex<SystemTime>>,
-    idle_timeout: Duration,
+    last_used: SystemTime,
+    size_bytes: u64,
+    load_time_ms: u64,
+}
+
+impl CachedModel {
+    /// Load a model into a fresh engine, timing the load
+    fn load(model_path: PathBuf, kind: EngineKind, use_gpu: bool) -> Result<Self, String> {
+        let size_bytes = model_size(&model_path).unwrap_or(0);
+        let started = Instant::now();
+        let engine = load_engine(&model_path, kind, use_gpu)?;
+        let load_time_ms = started.elapsed().as_millis() as u64;
+        println!(
+            "[Model Manager] Loaded {} model {:?} in {} ms",
+            kind.as_str(),
+            model_path,
+            load_time_ms
+        );
+
+        Ok(Self {
+            path: model_path,
+            kind,
+            use_gpu,
+            engine: Arc::new(Mutex::new(Some(engine))),
+            last_used: SystemTime::now(),
+            size_bytes,
+            load_time_ms,
+        })
+    }
+
+    fn matches(&self, model_path: &Path, kind: EngineKind, use_gpu: bool) -> bool {
+        self.path == model_path
+            && self.kind == kind
+            && (kind != EngineKind::Whisper || self.use_gpu == use_gpu)
+    }
+
+    fn info(&self) -> LoadedModelInfo {
+        LoadedModelInfo {
+            path: self.path.to_string_lossy().to_string(),
+            engine_type: self.kind.as_str().to_string(),
+            last_used_ms: self
+                .last_used
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0),
+            approximate_memory_bytes: estimated_runtime_bytes(&self.path),
+        }
+    }
+
+    /// Free the model's memory, waiting for a transcription still using it
+    fn unload(self) {
+        let mut engine_guard = self.engine.lock().unwrap();
+        if let Some(mut engine) = engine_guard.take() {
+            engine.unload();
+        }
+    }
+}
+
+pub struct ModelManager {
+    /// Loaded models, most recently used first
+    models: Mutex<Vec<CachedModel>>,
+    /// Number of models kept loaded at once
+    capacity: AtomicUsize,
+    /// Idle timeout in seconds, adjustable at runtime
+    idle_timeout_secs: AtomicU64,
+    /// App-wide inference thread count; 0 leaves it to the engine
+    inference_threads: AtomicUsize,
+    /// Whether whisper models are loaded for GPU inference
+    use_gpu: AtomicBool,
 }
 
 impl ModelManager {
     pub fn new() -> Self {
+        Self::new_with_capacity(DEFAULT_CACHE_CAPACITY)
+    }
+
+    /// Keep up to `capacity` models loaded (at least one), evicting the least
+    /// recently used when another is needed
+    pub fn new_with_capacity(capacity: usize) -> Self {
         Self {
-            engine: Arc::new(Mutex::new(None)),
-            current_model_path: Arc::new(Mutex::new(None)),
-            last_activity: Arc::new(Mutex::new(SystemTime::now())),
-            idle_timeout: Duration::from_secs(5 * 60), // 5 minutes default
+            models: Mutex::new(Vec::new()),
+            capacity: AtomicUsize::new(capacity.max(1)),
+            idle_timeout_secs: AtomicU64::new(DEFAULT_IDLE_TIMEOUT.as_secs()),
+            inference_threads: AtomicUsize::new(0),
+            // whisper-rs enables the GPU by default only when built with GPU support
+            use_gpu: AtomicBool::new(WhisperContextParameters::default().use_gpu),
         }
     }
 
-    pub fn get_or_load_parakeet(&self, model_path: PathBuf) -> Result<Arc<Mutex<Option<Engine>>>, String> {
-        let mut engine_guard = self.engine.lock().unwrap();
-        let mut current_path_guard = self.current_model_path.lock().unwrap();
-
-        // Check if we need to load a new model
-        let needs_load = match (&*engine_guard, &*current_path_guard) {
-            (None, _) => true,
-            (Some(_), Some(path)) if path != &model_path => {
-                // Different model requested, unload current one
-                if let Some(mut engine) = engine_guard.take() {
-                    engine.unload();
-                }
-                true
-            }
-            (Some(Engine::Whisper(_)), _) => {
-                // Wrong engine type, unload and reload
-                if let Some(mut engine) = engine_guard.take() {
-                    engine.unload();
-                }
-                true
-            }
-            _ => false,
+    /// Number of models kept loaded at once
+    pub fn capacity(&self) -> usize {
+        self.capacity.load(Ordering::Relaxed)
+    }
+
+    /// Change how many models are kept loaded, unloading the least recently
+    /// used ones if there are now too many
+    ///
+    /// The capacity is at least one. Returns the value actually stored.
+    pub fn set_capacity(&self, capacity: usize) -> usize {
+        let capacity = capacity.max(1);
+        self.capacity.store(capacity, Ordering::Relaxed);
+
+        let evicted = {
+            let mut models = self.models.lock().unwrap();
+            let keep = models.len().min(capacity);
+            models.split_off(keep)
         };
+        evicted.into_iter().for_each(CachedModel::unload);
+        capacity
+    }
 
-        if needs_load {
-            let mut engine = ParakeetEngine::new();
-            engine
-                .load_model_with_params(&model_path, ParakeetModelParams::int8())
-                .map_err(|e| format!("Failed to load Parakeet model: {}", e))?;
+    /// Thread count used when a transcription doesn't ask for one (`None` = engine default)
+    pub fn inference_threads(&self) -> Option<usize> {
+        match self.inference_threads.load(Ordering::Relaxed) {
+            0 => None,
+            threads => Some(threads),
+        }
+    }
+
+    /// Set the app-wide inference thread count, clamped to the available cores
+    ///
+    /// Passing 0 restores the engine default. Returns the value actually stored.
+    pub fn set_inference_threads(&self, threads: usize) -> usize {
+        let cores = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1);
+        let threads = threads.min(cores);
+        self.inference_threads.store(threads, Ordering::Relaxed);
+        threads
+    }
+
+    /// Whether whisper models are loaded for GPU inference
+    pub fn use_gpu(&self) -> bool {
+        self.use_gpu.load(Ordering::Relaxed)
+    }
 
-            *engine_guard = Some(Engine::Parakeet(engine));
-            *current_path_guard = Some(model_path);
+    /// Load whisper models for GPU (or CPU) inference from now on
+    ///
+    /// Whisper models loaded with the other setting are unloaded, so the next
+    /// transcription reloads its model once and reuses it after that.
+    pub fn set_use_gpu(&self, use_gpu: bool) {
+        if self.use_gpu.swap(use_gpu, Ordering::Relaxed) == use_gpu {
+            return;
         }
 
-        // Update last activity
-        *self.last_activity.lock().unwrap() = SystemTime::now();
+        let stale: Vec<CachedModel> = {
+            let mut models = self.models.lock().unwrap();
+            let (stale, current) = models
+                .drain(..)
+                .partition(|model| model.kind == EngineKind::Whisper && model.use_gpu != use_gpu);
+            *models = current;
+            stale
+        };
+        stale.into_iter().for_each(CachedModel::unload);
+    }
+
+    /// How long a model may sit unused before `unload_if_idle` frees it
+    pub fn idle_timeout(&self) -> Duration {
+        Duration::from_secs(self.idle_timeout_secs.load(Ordering::Relaxed))
+    }
+
+    pub fn set_idle_timeout(&self, timeout: Duration) {
+        self.idle_timeout_secs.store(timeout.as_secs(), Ordering::Relaxed);
+    }
 
-        Ok(self.engine.clone())
+    pub fn get_or_load_parakeet(&self, model_path: PathBuf) -> Result<Arc<Mutex<Option<Engine>>>, String> {
+        self.get_or_load(model_path, EngineKind::Parakeet)
     }
 
     pub fn get_or_load_whisper(&self, model_path: PathBuf) -> Result<Arc<Mutex<Option<Engine>>>, String> {
-        let mut engine_guard = self.engine.lock().unwrap();
-        let mut current_path_guard = self.current_model_path.lock().unwrap();
-
-        // Check if we need to load a new model
-        let needs_load = match (&*engine_guard, &*current_path_guard) {
-            (None, _) => true,
-            (Some(_), Some(path)) if path != &model_path => {
-                // Different model requested, unload current one
-                if let Some(mut engine) = engine_guard.take() {
-                    engine.unload();
-                }
-                true
+        self.get_or_load(model_path, EngineKind::Whisper)
+    }
+
+    fn get_or_load(&self, model_path: PathBuf, kind: EngineKind) -> Result<Arc<Mutex<Option<Engine>>>, String> {
+        let use_gpu = self.use_gpu();
+        let evicted = {
+            let mut models = self.models.lock().unwrap();
+
+            // Reuse a cached engine if one already has this model loaded
+            let cached = models.iter().position(|model| model.matches(&model_path, kind, use_gpu));
+            if let Some(index) = cached {
+                let mut model = models.remove(index);
+                model.last_used = SystemTime::now();
+                let engine = model.engine.clone();
+                models.insert(0, model);
+                return Ok(engine);
             }
-            (Some(Engine::Parakeet(_)), _) => {
-                // Wrong engine type, unload and reload
-                if let Some(mut engine) = engine_guard.take() {
-                    engine.unload();
+
+            // Make room first so the cache never holds more than its
+            // capacity in memory, even while loading
+            let keep = self.capacity().saturating_sub(1);
+            if models.len() > keep { models.split_off(keep) } else { Vec::new() }
+        };
+        // Unloading waits for transcriptions still using the evicted models,
+        // so it happens outside the lock
+        evicted.into_iter().for_each(CachedModel::unload);
+
+        let model = CachedModel::load(model_path, kind, use_gpu)?;
+
+        let (engine, evicted) = {
+            let mut models = self.models.lock().unwrap();
+            let cached = models.iter().position(|other| other.matches(&model.path, kind, use_gpu));
+            match cached {
+                // Another caller loaded the same model meanwhile; keep theirs
+                Some(index) => {
+                    let mut cached = models.remove(index);
+                    cached.last_used = SystemTime::now();
+                    let engine = cached.engine.clone();
+                    models.insert(0, cached);
+                    (engine, vec![model])
+                }
+                None => {
+                    let engine = model.engine.clone();
+                    models.insert(0, model);
+                    let keep = models.len().min(self.capacity());
+                    (engine, models.split_off(keep))
                 }
-                true
             }
-            _ => false,
         };
+        evicted.into_iter().for_each(CachedModel::unload);
 
-        if needs_load {
-            let mut engine = WhisperEngine::new();
-            engine
-                .load_model(&model_path)
-                .map_err(|e| format!("Failed to load Whisper model: {}", e))?;
+        Ok(engine)
+    }
 
-            *engine_guard = Some(Engine::Whisper(engine));
-            *current_path_guard = Some(model_path);
+    /// Load a model and make it the most recently used one without a gap
+    ///
+    /// The new model is fully loaded before it enters the cache, so
+    /// transcriptions that start during the load still get the old model, and
+    /// transcriptions already running on an evicted model finish undisturbed.
+    pub fn switch_model(&self, model_path: PathBuf, kind: EngineKind) -> Result<(), String> {
+        let use_gpu = self.use_gpu();
+        {
+            let mut models = self.models.lock().unwrap();
+            let cached = models.iter().position(|model| model.matches(&model_path, kind, use_gpu));
+            if let Some(index) = cached {
+                let mut model = models.remove(index);
+                model.last_used = SystemTime::now();
+                models.insert(0, model);
+                return Ok(());
+            }
         }
 
-        // Update last activity
-        *self.last_activity.lock().unwrap() = SystemTime::now();
+        // Load outside the lock so the cached models stay usable meanwhile
+        let model = CachedModel::load(model_path, kind, use_gpu)?;
 
-        Ok(self.engine.clone())
+        let evicted = {
+            let mut models = self.models.lock().unwrap();
+            models.insert(0, model);
+            let keep = models.len().min(self.capacity());
+            models.split_off(keep)
+        };
+        evicted.into_iter().for_each(CachedModel::unload);
+
+        Ok(())
     }
 
-    pub fn unload_if_idle(&self) {
-        let last_activity = *self.last_activity.lock().unwrap();
-        let elapsed = SystemTime::now()
-            .duration_since(last_activity)
-            .unwrap_or(Duration::from_secs(0));
+    /// Warm up the most recently used model with a dummy inference, discarding the result
+    pub fn warm_up(&self) -> Result<(), String> {
+        let engine = match self.models.lock().unwrap().first() {
+            Some(model) => model.engine.clone(),
+            None => return Err("No model is loaded".to_string()),
+        };
 
-        if elapsed > self.idle_timeout {
-            let mut engine_guard = self.engine.lock().unwrap();
-            if let Some(mut engine) = engine_guard.take() {
-                engine.unload();
-            }
-            *self.current_model_path.lock().unwrap() = None;
-        }
+        warm_up_engine(&engine)
     }
 
-    pub fn unload_model(&self) {
-        let mut engine_guard = self.engine.lock().unwrap();
-        if let Some(mut engine) = engine_guard.take() {
-            engine.unload();
+    /// Load a model if it isn't already and warm it up with a dummy inference,
+    /// returning how long the inference took (loading excluded)
+    pub fn warm_up_model(&self, model_path: PathBuf, kind: EngineKind) -> Result<Duration, String> {
+        let engine = self.get_or_load(model_path, kind)?;
+        let started = Instant::now();
+        warm_up_engine(&engine).map_err(|e| format!("Warmup inference failed: {}", e))?;
+        Ok(started.elapsed())
+    }
+
+    /// Models currently held in memory, most recently used first
+    pub fn loaded_models(&self) -> Vec<LoadedModelInfo> {
+        self.models.lock().unwrap().iter().map(CachedModel::info).collect()
+    }
+
+    /// Whether any model is loaded
+    pub fn is_loaded(&self) -> bool {
+        !self.models.lock().unwrap().is_empty()
+    }
+
+    /// Details of the most recently used model, if any is loaded
+    pub fn loaded_model_info(&self) -> Option<ModelInfo> {
+        self.models.lock().unwrap().first().map(|model| ModelInfo {
+            path: model.path.to_string_lossy().to_string(),
+            engine_type: model.kind.as_str().to_string(),
+            size_bytes: model.size_bytes,
+            load_time_ms: model.load_time_ms,
+        })
+    }
+
+    /// Unload the model at `model_path` if it is loaded, returning whether it was
+    ///
+    /// A transcription already running on it finishes first, then the memory
+    /// is freed.
+    pub fn evict(&self, model_path: &Path) -> bool {
+        let evicted: Vec<CachedModel> = {
+            let mut models = self.models.lock().unwrap();
+            let (evicted, kept) = models.drain(..).partition(|model| model.path == model_path);
+            *models = kept;
+            evicted
+        };
+
+        let unloaded = !evicted.is_empty();
+        evicted.into_iter().for_each(CachedModel::unload);
+        unloaded
+    }
+
+    /// Unload models that have been unused for longer than the idle timeout,
+    /// returning whether any were unloaded
+    pub fn unload_if_idle(&self) -> bool {
+        let idle_timeout = self.idle_timeout();
+        let now = SystemTime::now();
+        let is_idle = |model: &CachedModel| {
+            now.duration_since(model.last_used)
+                .unwrap_or(Duration::from_secs(0))
+                > idle_timeout
+        };
+
+        let idle: Vec<CachedModel> = {
+            let mut models = self.models.lock().unwrap();
+            let (idle, active) = models.drain(..).partition(|model| is_idle(model));
+            *models = active;
+            idle
+        };
+
+        let unloaded = !idle.is_empty();
+        idle.into_iter().for_each(CachedModel::unload);
+        unloaded
+    }
+
+    /// Unload every model, returning the ones that were loaded
+    pub fn unload_model(&self) -> Vec<LoadedModelInfo> {
+        let models = std::mem::take(&mut *self.models.lock().unwrap());
+        let unloaded = models.iter().map(CachedModel::info).collect();
+        models.into_iter().for_each(CachedModel::unload);
+        unloaded
+    }
+}
+
+fn warm_up_engine(engine: &Mutex<Option<Engine>>) -> Result<(), String> {
+    let mut engine_guard = engine.lock().unwrap();
+    match engine_guard.as_mut() {
+        Some(engine) => engine.warm_up(),
+        None => Err("Model failed to load".to_string()),
+    }
+}
+
+/// Load a model into a fresh engine of the requested kind
+fn load_engine(model_path: &Path, kind: EngineKind, use_gpu: bool) -> Result<Engine, String> {
+    match kind {
+        EngineKind::Parakeet => {
+            let mut engine = ParakeetEngine::new();
+            engine
+                .load_model_with_params(model_path, ParakeetModelParams::int8())
+                .map_err(|e| format!("Failed to load Parakeet model: {}", e))?;
+            Ok(Engine::Parakeet(engine))
+        }
+        EngineKind::Whisper => {
+            let mut engine = WhisperCppEngine::new();
+            engine
+                .load_model(model_path, use_gpu)
+                .map_err(|e| format!("Failed to load Whisper model: {}", e))?;
+            Ok(Engine::Whisper(engine))
         }
-        *self.current_model_path.lock().unwrap() = None;
     }
 }
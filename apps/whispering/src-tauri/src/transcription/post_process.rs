@@ -0,0 +1,97 @@
+use super::export::Segment;
+use crate::command::pipe_through_command;
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::time::Duration;
+
+/// How long a user post-processing command may run before it is killed
+const POST_PROCESS_TIMEOUT: Duration = Duration::from_secs(30);
+
+lazy_static! {
+    /// Non-speech annotations whisper emits instead of (or alongside) words:
+    /// - anything in square brackets, e.g. `[MUSIC]`, `[BLANK_AUDIO]`, `[ Inaudible ]`
+    ///   (whisper only uses brackets for annotations)
+    /// - parentheses that mention a sound cue, e.g. `(applause)`, `(upbeat music)`,
+    ///   `(coughs)`; other parenthesised text is kept since it may be speech
+    /// - music note markers (`♪ lyrics ♪`, lone `♪`)
+    static ref ANNOTATION_PATTERNS: [Regex; 3] = [
+        Regex::new(r"\[[^\[\]]{1,60}\]").unwrap(),
+        Regex::new(
+            r"(?i)\([^()]{0,40}\b(?:music|applause|laugh\w*|chuckl\w*|silence|noise|inaudible|cough\w*|sigh\w*|static|beep\w*|blank_audio|clap\w*|cheer\w*|breath\w*|footsteps|crosstalk)\b[^()]{0,40}\)"
+        )
+        .unwrap(),
+        Regex::new(r"♪[^♪]{0,200}♪|♪").unwrap(),
+    ];
+    static ref EXTRA_SPACES: Regex = Regex::new(r"[ \t]{2,}").unwrap();
+}
+
+/// Remove bracketed/parenthesized non-speech annotations from transcript text
+///
+/// See `ANNOTATION_PATTERNS` for what counts as an annotation. Leftover runs
+/// of spaces are collapsed and the result is trimmed.
+pub fn strip_annotations(text: &str) -> String {
+    let mut stripped = text.to_string();
+    for pattern in ANNOTATION_PATTERNS.iter() {
+        stripped = pattern.replace_all(&stripped, "").into_owned();
+    }
+    EXTRA_SPACES.replace_all(&stripped, " ").trim().to_string()
+}
+
+/// Whether text ends a sentence (ignoring trailing quotes/brackets)
+fn ends_sentence(text: &str) -> bool {
+    text.trim_end()
+        .trim_end_matches(|c| matches!(c, '"' | '\'' | ')' | ']' | '”' | '’'))
+        .ends_with(|c| matches!(c, '.' | '!' | '?' | '…'))
+}
+
+/// Join segments into prose, starting a new paragraph at long pauses
+///
+/// A paragraph break is inserted when the silence between two segments is at
+/// least `paragraph_gap_ms` and the previous segment ends a sentence. Pauses of
+/// twice the gap or more always break, even mid-sentence, since the speaker
+/// has clearly moved on. Paragraphs are separated by a blank line.
+pub fn format_paragraphs(segments: &[Segment], paragraph_gap_ms: i64) -> String {
+    let mut output = String::new();
+    let mut previous: Option<&Segment> = None;
+
+    for segment in segments {
+        let text = segment.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if let Some(prev) = previous {
+            let gap = segment.start_ms - prev.end_ms;
+            let paragraph_break = gap >= paragraph_gap_ms.saturating_mul(2)
+                || (gap >= paragraph_gap_ms && ends_sentence(&prev.text));
+            output.push_str(if paragraph_break { "\n\n" } else { " " });
+        }
+
+        output.push_str(text);
+        previous = Some(segment);
+    }
+
+    output
+}
+
+/// Pipe the transcript through a user-supplied command and use its stdout
+///
+/// Lets users plug in their own spell-checkers, LLM cleanup or formatters.
+/// If the command fails, times out or prints nothing, the transcript is
+/// returned unchanged rather than losing the user's dictation.
+pub fn run_post_process_command(text: String, command: &str) -> String {
+    match pipe_through_command(command, &text, POST_PROCESS_TIMEOUT) {
+        Ok(output) if !output.trim().is_empty() => output.trim().to_string(),
+        Ok(_) => {
+            eprintln!(
+                "[Post Process] '{}' produced no output, keeping the raw transcript",
+                command
+            );
+            text
+        }
+        Err(e) => {
+            eprintln!("[Post Process] {}, keeping the raw transcript", e);
+            text
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use super::ffmpeg::ffmpeg_available;
+use super::symphonia_decode::sniff_extension;
+use super::{is_valid_wav_format, is_wav_container};
+use crate::audio_ops::read_wav_samples;
+use serde::Serialize;
+use std::path::Path;
+
+/// Recordings shorter than this are too short to contain speech
+const MIN_DURATION_SECS: f64 = 0.1;
+/// Recordings longer than this are almost certainly a mistake for dictation
+const MAX_DURATION_SECS: f64 = 4.0 * 60.0 * 60.0;
+/// Audio whose peak stays below this level (dBFS) is treated as silent
+const SILENCE_PEAK_DB: f32 = -60.0;
+
+/// Outcome of a single precheck
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CheckStatus {
+    Pass,
+    Fail,
+    /// The check couldn't run, usually because an earlier one failed
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckResult {
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+impl CheckResult {
+    fn pass(message: impl Into<String>) -> Self {
+        Self { status: CheckStatus::Pass, message: message.into() }
+    }
+
+    fn fail(message: impl Into<String>) -> Self {
+        Self { status: CheckStatus::Fail, message: message.into() }
+    }
+
+    fn skipped(message: impl Into<String>) -> Self {
+        Self { status: CheckStatus::Skipped, message: message.into() }
+    }
+}
+
+/// Per-check report on whether a file can be transcribed - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioPrecheckReport {
+    /// True when no check failed
+    pub transcribable: bool,
+    pub exists: CheckResult,
+    pub readable: CheckResult,
+    pub format: CheckResult,
+    pub non_silent: CheckResult,
+    pub duration: CheckResult,
+    pub needs_ffmpeg: bool,
+    pub duration_secs: Option<f64>,
+}
+
+/// Check that a file can be transcribed before queueing it
+///
+/// Runs cheap checks in order: the file exists and is readable, its format is
+/// decodable in Rust or convertible (noting when FFmpeg is required), and for
+/// WAV files that it isn't silent and has a sensible duration. Compressed
+/// formats are not decoded here, so their silence/duration checks are skipped.
+#[tauri::command]
+pub async fn precheck_audio(path: String) -> AudioPrecheckReport {
+    let file_path = Path::new(&path);
+    let mut report = AudioPrecheckReport {
+        transcribable: false,
+        exists: CheckResult::skipped("Not checked"),
+        readable: CheckResult::skipped("Not checked"),
+        format: CheckResult::skipped("Not checked"),
+        non_silent: CheckResult::skipped("Not checked"),
+        duration: CheckResult::skipped("Not checked"),
+        needs_ffmpeg: false,
+        duration_secs: None,
+    };
+
+    if !file_path.is_file() {
+        report.exists = CheckResult::fail(format!("File not found: {}", path));
+        return report;
+    }
+    report.exists = CheckResult::pass("File exists");
+
+    let audio_data = match std::fs::read(file_path) {
+        Ok(data) if data.is_empty() => {
+            report.readable = CheckResult::fail("File is empty");
+            return report;
+        }
+        Ok(data) => data,
+        Err(e) => {
+            report.readable = CheckResult::fail(format!("Failed to read file: {}", e));
+            return report;
+        }
+    };
+    report.readable = CheckResult::pass(format!("Read {} bytes", audio_data.len()));
+
+    let wav = is_wav_container(&audio_data)
+        .then(|| read_wav_samples(file_path))
+        .and_then(|result| result.ok());
+
+    match &wav {
+        Some(_) if is_valid_wav_format(&audio_data) => {
+            report.format = CheckResult::pass("Already 16kHz mono 16-bit WAV");
+        }
+        Some((spec, _)) => {
+            report.format = CheckResult::pass(format!(
+                "WAV ({} Hz, {} channels, {}-bit) will be converted without FFmpeg",
+                spec.sample_rate, spec.channels, spec.bits_per_sample
+            ));
+        }
+        None => match sniff_extension(&audio_data) {
+            Some(extension) => {
+                report.format = CheckResult::pass(format!(
+                    "{} audio will be decoded without FFmpeg",
+                    extension.to_uppercase()
+                ));
+            }
+            None => {
+                report.needs_ffmpeg = true;
+                report.format = if ffmpeg_available() {
+                    CheckResult::pass("Unrecognized format; will be converted with FFmpeg")
+                } else {
+                    CheckResult::fail(
+                        "This format needs FFmpeg to convert, and FFmpeg is not installed",
+                    )
+                };
+            }
+        },
+    }
+
+    if let Some((spec, samples)) = &wav {
+        let duration_secs = samples.len() as f64 / (spec.sample_rate as f64 * spec.channels as f64);
+        report.duration_secs = Some(duration_secs);
+        report.duration = if duration_secs < MIN_DURATION_SECS {
+            CheckResult::fail(format!("Recording is too short ({:.2}s)", duration_secs))
+        } else if duration_secs > MAX_DURATION_SECS {
+            CheckResult::fail(format!("Recording is too long ({:.0} minutes)", duration_secs / 60.0))
+        } else {
+            CheckResult::pass(format!("{:.2}s", duration_secs))
+        };
+
+        let peak = samples.iter().fold(0.0f32, |peak, s| peak.max(s.abs()));
+        let peak_db = 20.0 * peak.max(1e-10).log10();
+        report.non_silent = if peak_db < SILENCE_PEAK_DB {
+            CheckResult::fail(format!("Recording is silent (peak {:.1} dBFS)", peak_db))
+        } else {
+            CheckResult::pass(format!("Peak {:.1} dBFS", peak_db))
+        };
+    } else {
+        let reason = "Only checked for WAV files";
+        report.duration = CheckResult::skipped(reason);
+        report.non_silent = CheckResult::skipped(reason);
+    }
+
+    report.transcribable = [
+        &report.exists,
+        &report.readable,
+        &report.format,
+        &report.non_silent,
+        &report.duration,
+    ]
+    .iter()
+    .all(|check| check.status != CheckStatus::Fail);
+
+    println!(
+        "[Precheck] {}: transcribable={}, needs_ffmpeg={}, duration={:?}",
+        path, report.transcribable, report.needs_ffmpeg, report.duration_secs
+    );
+
+    report
+}
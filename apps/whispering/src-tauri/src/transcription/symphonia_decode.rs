@@ -0,0 +1,141 @@
+use super::cancellation::CancellationToken;
+use super::error::TranscriptionError;
+use symphonia::core::audio::SampleBuffer;
+use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::errors::Error as SymphoniaError;
+use symphonia::core::formats::FormatOptions;
+use symphonia::core::io::MediaSourceStream;
+use symphonia::core::meta::MetadataOptions;
+use symphonia::core::probe::Hint;
+
+/// Decoded audio as interleaved f32 samples in [-1.0, 1.0]
+pub struct DecodedAudio {
+    pub samples: Vec<f32>,
+    pub sample_rate: u32,
+    pub channels: usize,
+}
+
+/// Guess a file extension for the container from its magic bytes
+///
+/// Returns `None` for data that doesn't look like a format symphonia decodes,
+/// so callers can skip straight to FFmpeg.
+pub fn sniff_extension(audio_data: &[u8]) -> Option<&'static str> {
+    let starts_with = |magic: &[u8]| audio_data.starts_with(magic);
+
+    if starts_with(b"fLaC") {
+        Some("flac")
+    } else if starts_with(b"OggS") {
+        Some("ogg")
+    } else if starts_with(b"ID3") {
+        Some("mp3")
+    } else if super::is_wav_container(audio_data) {
+        Some("wav")
+    } else if audio_data.len() >= 8 && &audio_data[4..8] == b"ftyp" {
+        Some("m4a")
+    } else if starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        Some("mka")
+    } else if audio_data.len() >= 2 && audio_data[0] == 0xFF && audio_data[1] & 0xF6 == 0xF0 {
+        // ADTS sync word with layer 0: raw AAC
+        Some("aac")
+    } else if audio_data.len() >= 2 && audio_data[0] == 0xFF && audio_data[1] & 0xE0 == 0xE0 {
+        // MPEG audio frame sync without an ID3 tag
+        Some("mp3")
+    } else {
+        None
+    }
+}
+
+fn read_error(message: String) -> TranscriptionError {
+    eprintln!("[Symphonia Decode] {}", message);
+    TranscriptionError::audio_read(message)
+}
+
+/// Decode the first audio track of a compressed file (MP3, OGG Vorbis, FLAC,
+/// AAC, ...) without FFmpeg
+///
+/// Packets that fail to decode are skipped, as players do, so a few corrupt
+/// frames don't lose the whole recording.
+pub fn decode(
+    audio_data: Vec<u8>,
+    extension: &str,
+    cancel: Option<&CancellationToken>,
+) -> Result<DecodedAudio, TranscriptionError> {
+    let mut hint = Hint::new();
+    hint.with_extension(extension);
+    let cursor = Box::new(std::io::Cursor::new(audio_data));
+    let source = MediaSourceStream::new(cursor, Default::default());
+
+    let probed = symphonia::default::get_probe()
+        .format(&hint, source, &FormatOptions::default(), &MetadataOptions::default())
+        .map_err(|e| read_error(format!("Unrecognized {} data: {}", extension, e)))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or_else(|| read_error("No audio track found".to_string()))?;
+    let track_id = track.id;
+    let mut sample_rate = track.codec_params.sample_rate;
+    let mut channels = track.codec_params.channels.map(|channels| channels.count());
+
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| read_error(format!("Unsupported codec: {}", e)))?;
+
+    let mut samples = Vec::new();
+    let mut skipped_packets = 0;
+    loop {
+        if cancel.is_some_and(|token| token.is_cancelled()) {
+            return Err(TranscriptionError::Cancelled {
+                message: "Audio conversion was cancelled".to_string(),
+            });
+        }
+
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            // The format reader signals the end of the stream with an EOF error
+            Err(SymphoniaError::IoError(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => {
+                break
+            }
+            Err(e) => return Err(read_error(format!("Failed to read packet: {}", e))),
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+
+        match decoder.decode(&packet) {
+            Ok(decoded) => {
+                let spec = *decoded.spec();
+                sample_rate.get_or_insert(spec.rate);
+                channels.get_or_insert(spec.channels.count());
+
+                let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+                buffer.copy_interleaved_ref(decoded);
+                samples.extend_from_slice(buffer.samples());
+            }
+            Err(SymphoniaError::DecodeError(_)) => skipped_packets += 1,
+            Err(e) => return Err(read_error(format!("Failed to decode audio: {}", e))),
+        }
+    }
+
+    if skipped_packets > 0 {
+        eprintln!("[Symphonia Decode] WARNING: skipped {} undecodable packets", skipped_packets);
+    }
+
+    let sample_rate = sample_rate.ok_or_else(|| read_error("Unknown sample rate".to_string()))?;
+    let channels = channels.unwrap_or(1).max(1);
+    println!(
+        "[Symphonia Decode] Decoded {} samples ({} Hz, {} channels) from {}",
+        samples.len(),
+        sample_rate,
+        channels,
+        extension
+    );
+
+    Ok(DecodedAudio {
+        samples,
+        sample_rate,
+        channels,
+    })
+}
@@ -0,0 +1,111 @@
+use super::error::TranscriptionError;
+use super::export::Segment;
+use super::whisper_cpp::WhisperOptions;
+use super::{load_prompt_file, post_process, whisper_samples, with_whisper_engine, ModelManager};
+use serde::Serialize;
+
+/// A transcribed segment with its English translation - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslatedSegment {
+    pub start_ms: i64,
+    pub end_ms: i64,
+    pub original: String,
+    pub translation: String,
+}
+
+/// Milliseconds shared by two time spans
+fn overlap_ms(a: &Segment, b: &Segment) -> i64 {
+    (a.end_ms.min(b.end_ms) - a.start_ms.max(b.start_ms)).max(0)
+}
+
+/// Index of the original segment a translated segment belongs to
+///
+/// The two passes segment the audio independently, so a translated segment is
+/// assigned to the original it overlaps most, or the nearest one if it
+/// overlaps none.
+fn best_match(originals: &[Segment], translated: &Segment) -> usize {
+    let midpoint = (translated.start_ms + translated.end_ms) / 2;
+    (0..originals.len())
+        .max_by_key(|&i| {
+            let distance = (originals[i].start_ms + originals[i].end_ms) / 2 - midpoint;
+            (overlap_ms(&originals[i], translated), -distance.abs())
+        })
+        .unwrap_or(0)
+}
+
+/// Pair each original segment with the translated text covering the same time
+fn align(originals: Vec<Segment>, translated: &[Segment]) -> Vec<TranslatedSegment> {
+    let mut translations = vec![Vec::new(); originals.len()];
+    if !originals.is_empty() {
+        for segment in translated {
+            translations[best_match(&originals, segment)].push(segment.text.as_str());
+        }
+    }
+
+    originals
+        .into_iter()
+        .zip(translations)
+        .map(|(segment, parts)| TranslatedSegment {
+            start_ms: segment.start_ms,
+            end_ms: segment.end_ms,
+            original: segment.text,
+            translation: parts.join(" "),
+        })
+        .collect()
+}
+
+/// Transcribe audio and translate it to English in one call, aligned by segment
+///
+/// Runs whisper twice over the same converted samples: once transcribing in
+/// the source language and once with translation enabled. The original
+/// pass's segments are kept and each is paired with the translated text that
+/// overlaps it in time, which suits subtitle workflows showing both.
+#[tauri::command]
+pub async fn transcribe_with_translation(
+    audio_data: Vec<u8>,
+    model_path: String,
+    language: Option<String>,
+    options: Option<WhisperOptions>,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<Vec<TranslatedSegment>, TranscriptionError> {
+    let mut options = options.unwrap_or_default();
+    options.threads = options.threads.or_else(|| model_manager.inference_threads());
+    load_prompt_file(&mut options)?;
+
+    // Convert once and reuse the samples for both passes
+    let samples = whisper_samples(audio_data, &options)?;
+    if samples.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let strip_annotations = options.strip_annotations.unwrap_or(false);
+    let original_options = WhisperOptions {
+        translate: Some(false),
+        ..options.clone()
+    };
+    let translate_options = WhisperOptions {
+        translate: Some(true),
+        ..options
+    };
+
+    let (mut original, mut translated) = with_whisper_engine(&model_manager, &model_path, |engine| {
+        let original = engine.transcribe(&samples, language.as_deref(), &original_options)?;
+        let translated = engine.transcribe(&samples, language.as_deref(), &translate_options)?;
+        Ok((original, translated))
+    })?;
+
+    if strip_annotations {
+        for segment in original.segments.iter_mut().chain(translated.segments.iter_mut()) {
+            segment.text = post_process::strip_annotations(&segment.text);
+        }
+    }
+
+    println!(
+        "[Translate] {} original segments, {} translated segments",
+        original.segments.len(),
+        translated.segments.len()
+    );
+
+    Ok(align(original.segments, &translated.segments))
+}
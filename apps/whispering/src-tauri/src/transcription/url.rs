@@ -0,0 +1,175 @@
+use super::error::TranscriptionError;
+use super::whisper_cpp::WhisperOptions;
+use super::{transcribe_whisper, ModelManager};
+use serde::Serialize;
+use std::io::Write;
+use tauri::{AppHandle, Emitter, Url};
+use tauri_plugin_http::reqwest;
+
+/// Event emitted while `transcribe_url` downloads its audio
+pub const URL_DOWNLOAD_PROGRESS_EVENT: &str = "url-download-progress";
+
+/// Largest download accepted, to keep a bad URL from filling the disk
+const MAX_DOWNLOAD_BYTES: u64 = 1024 * 1024 * 1024;
+
+/// Minimum number of bytes between progress events
+const PROGRESS_INTERVAL_BYTES: u64 = 512 * 1024;
+
+/// Download progress - emitted to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlDownloadProgress {
+    pub url: String,
+    pub downloaded_bytes: u64,
+    /// From `Content-Length`, when the server sends it
+    pub total_bytes: Option<u64>,
+}
+
+fn download_error(message: String) -> TranscriptionError {
+    TranscriptionError::audio_read(message)
+}
+
+/// Only plain http(s) URLs may be fetched
+fn parse_url(url: &str) -> Result<Url, TranscriptionError> {
+    let parsed =
+        Url::parse(url).map_err(|e| download_error(format!("Invalid URL {}: {}", url, e)))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(parsed),
+        scheme => Err(download_error(format!(
+            "Unsupported URL scheme '{}'; only http and https are allowed",
+            scheme
+        ))),
+    }
+}
+
+/// Whether a `Content-Type` can hold audio the conversion pipeline understands
+///
+/// Servers often send containers as video or as a generic binary type, so
+/// those are accepted too; pages and other text are rejected.
+fn is_audio_content_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or("").trim().to_lowercase();
+    mime.starts_with("audio/")
+        || mime.starts_with("video/")
+        || matches!(
+            mime.as_str(),
+            "application/octet-stream" | "application/ogg" | "binary/octet-stream"
+        )
+}
+
+/// Stream the response body to a temp file, enforcing the size cap
+async fn download(
+    url: &Url,
+    app_handle: &AppHandle,
+) -> Result<tempfile::NamedTempFile, TranscriptionError> {
+    let mut response = reqwest::get(url.clone())
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| download_error(format!("Failed to download {}: {}", url, e)))?;
+
+    if let Some(content_type) = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+    {
+        if !is_audio_content_type(content_type) {
+            return Err(TranscriptionError::AudioFormatNotSupported {
+                message: format!("{} is not audio (Content-Type: {})", url, content_type),
+            });
+        }
+    }
+
+    let total_bytes = response.content_length();
+    if total_bytes.is_some_and(|total| total > MAX_DOWNLOAD_BYTES) {
+        return Err(download_error(format!(
+            "{} is too large ({} bytes, limit {} bytes)",
+            url,
+            total_bytes.unwrap_or_default(),
+            MAX_DOWNLOAD_BYTES
+        )));
+    }
+
+    let mut file = tempfile::Builder::new()
+        .suffix(".audio")
+        .tempfile()
+        .map_err(|e| download_error(format!("Failed to create temp file: {}", e)))?;
+
+    let mut downloaded_bytes = 0u64;
+    let mut last_progress = 0u64;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| download_error(format!("Download of {} failed: {}", url, e)))?
+    {
+        downloaded_bytes += chunk.len() as u64;
+        // Content-Length can be missing or wrong, so check as we go
+        if downloaded_bytes > MAX_DOWNLOAD_BYTES {
+            return Err(download_error(format!(
+                "{} exceeds the {} byte download limit",
+                url, MAX_DOWNLOAD_BYTES
+            )));
+        }
+
+        file.write_all(&chunk)
+            .map_err(|e| download_error(format!("Failed to write downloaded audio: {}", e)))?;
+
+        if downloaded_bytes - last_progress >= PROGRESS_INTERVAL_BYTES {
+            last_progress = downloaded_bytes;
+            let _ = app_handle.emit(
+                URL_DOWNLOAD_PROGRESS_EVENT,
+                UrlDownloadProgress {
+                    url: url.to_string(),
+                    downloaded_bytes,
+                    total_bytes,
+                },
+            );
+        }
+    }
+
+    // Always report completion
+    let _ = app_handle.emit(
+        URL_DOWNLOAD_PROGRESS_EVENT,
+        UrlDownloadProgress {
+            url: url.to_string(),
+            downloaded_bytes,
+            total_bytes,
+        },
+    );
+
+    file.flush()
+        .map_err(|e| download_error(format!("Failed to write downloaded audio: {}", e)))?;
+    Ok(file)
+}
+
+/// Download audio from an http(s) URL and transcribe it with a whisper model
+///
+/// The body is streamed to a temp file (up to 1 GiB), emitting
+/// `url-download-progress` events, then run through the same conversion and
+/// transcription as local files. Responses whose `Content-Type` isn't audio,
+/// video or generic binary are rejected.
+#[tauri::command]
+pub async fn transcribe_url(
+    url: String,
+    model_path: String,
+    language: Option<String>,
+    options: Option<WhisperOptions>,
+    model_manager: tauri::State<'_, ModelManager>,
+    app_handle: AppHandle,
+) -> Result<String, TranscriptionError> {
+    let url = parse_url(&url)?;
+
+    println!("[Transcribe URL] Downloading {}", url);
+    let file = download(&url, &app_handle).await?;
+
+    let audio_data = std::fs::read(file.path())
+        .map_err(|e| download_error(format!("Failed to read downloaded audio: {}", e)))?;
+    println!("[Transcribe URL] Downloaded {} bytes", audio_data.len());
+
+    transcribe_whisper(
+        audio_data,
+        &model_path,
+        language.as_deref(),
+        options.unwrap_or_default(),
+        &model_manager,
+        None,
+    )
+}
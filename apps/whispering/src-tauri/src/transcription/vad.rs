@@ -0,0 +1,67 @@
+use super::export::{Segment, WordTimestamp};
+use crate::audio_ops::{map_trimmed_time_to_original, rms_db, KeptRegion};
+
+/// Energy threshold used when a caller enables VAD without choosing one (dBFS)
+pub const DEFAULT_VAD_ENERGY_THRESHOLD_DB: f32 = -40.0;
+
+/// Analysis frame length at 16kHz (30 ms)
+const FRAME_SAMPLES: usize = 480;
+
+/// Samples per millisecond at 16kHz
+const SAMPLES_PER_MS: usize = 16;
+
+/// 16kHz mono samples with silent frames removed
+pub struct VadOutput {
+    pub samples: Vec<f32>,
+    /// Kept stretches of the original audio, for mapping timestamps back
+    pub mapping: Vec<KeptRegion>,
+}
+
+/// Drop 30 ms frames whose RMS level is below `threshold_db` (dBFS)
+///
+/// A cheap energy gate rather than a speech model: it saves inference time on
+/// long recordings with a lot of silence, and stops whisper from hallucinating
+/// text over it. Adjacent kept frames are merged into a single region.
+pub fn filter_silent_frames(samples: &[f32], threshold_db: f32) -> VadOutput {
+    let mut kept = Vec::with_capacity(samples.len());
+    let mut mapping: Vec<KeptRegion> = Vec::new();
+
+    for (index, frame) in samples.chunks(FRAME_SAMPLES).enumerate() {
+        let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+        if rms_db(mean_square) < threshold_db {
+            continue;
+        }
+
+        let original_start_ms = (index * FRAME_SAMPLES / SAMPLES_PER_MS) as i64;
+        let duration_ms = (frame.len() / SAMPLES_PER_MS) as i64;
+        match mapping.last_mut() {
+            // Extend the region if this frame directly follows it
+            Some(region) if region.original_start_ms + region.duration_ms == original_start_ms => {
+                region.duration_ms += duration_ms;
+            }
+            _ => mapping.push(KeptRegion {
+                original_start_ms,
+                trimmed_start_ms: (kept.len() / SAMPLES_PER_MS) as i64,
+                duration_ms,
+            }),
+        }
+        kept.extend_from_slice(frame);
+    }
+
+    VadOutput {
+        samples: kept,
+        mapping,
+    }
+}
+
+/// Shift segment timestamps from the filtered audio back to the original
+pub fn remap_segment(segment: &mut Segment, mapping: &[KeptRegion]) {
+    segment.start_ms = map_trimmed_time_to_original(segment.start_ms, mapping);
+    segment.end_ms = map_trimmed_time_to_original(segment.end_ms, mapping);
+}
+
+/// Shift word timestamps from the filtered audio back to the original
+pub fn remap_word(word: &mut WordTimestamp, mapping: &[KeptRegion]) {
+    word.start_ms = map_trimmed_time_to_original(word.start_ms, mapping);
+    word.end_ms = map_trimmed_time_to_original(word.end_ms, mapping);
+}
@@ -0,0 +1,105 @@
+use super::error::TranscriptionError;
+use super::whisper_cpp::WhisperOptions;
+use super::{transcribe_whisper, ModelManager};
+use serde::Serialize;
+
+/// How closely a transcription matches an expected script - returned to frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptVerification {
+    pub text: String,
+    /// Word error rate against the expected text (substitutions + deletions +
+    /// insertions over the number of expected words); 0.0 is a perfect match
+    /// and values above 1.0 are possible when many words are inserted
+    pub word_error_rate: f64,
+    /// `1 - word_error_rate`, clamped to 0.0..=1.0
+    pub similarity: f64,
+    pub expected_words: usize,
+    pub transcribed_words: usize,
+}
+
+/// Lowercase a word and strip its punctuation
+pub(super) fn normalize_word(word: &str) -> String {
+    word.chars()
+        .filter(|c| c.is_alphanumeric() || *c == '\'')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Lowercase words with punctuation stripped, so formatting differences don't count as errors
+fn normalize_words(text: &str) -> Vec<String> {
+    text.split_whitespace()
+        .map(normalize_word)
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+/// Word-level Levenshtein distance between two word sequences
+pub(super) fn edit_distance(reference: &[String], hypothesis: &[String]) -> usize {
+    let mut previous: Vec<usize> = (0..=hypothesis.len()).collect();
+    let mut current = vec![0; hypothesis.len() + 1];
+
+    for (i, ref_word) in reference.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, hyp_word) in hypothesis.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ref_word != hyp_word);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[hypothesis.len()]
+}
+
+/// Transcribe audio and score it against the script it is expected to match
+///
+/// The expected text is used as the decoder's initial prompt (unless `options`
+/// sets one) to bias it towards the script's vocabulary, then the word error
+/// rate between the script and the output is computed. Meant for QA workflows
+/// such as checking a voiceover against its script.
+#[tauri::command]
+pub async fn verify_transcript(
+    audio_data: Vec<u8>,
+    model_path: String,
+    expected_text: String,
+    language: Option<String>,
+    options: Option<WhisperOptions>,
+    model_manager: tauri::State<'_, ModelManager>,
+) -> Result<TranscriptVerification, TranscriptionError> {
+    let mut options = options.unwrap_or_default();
+    if options.initial_prompt.is_none() {
+        options.initial_prompt = Some(expected_text.clone());
+    }
+
+    let text = transcribe_whisper(
+        audio_data,
+        &model_path,
+        language.as_deref(),
+        options,
+        &model_manager,
+        None,
+    )?;
+
+    let expected = normalize_words(&expected_text);
+    let transcribed = normalize_words(&text);
+    let word_error_rate = if expected.is_empty() {
+        if transcribed.is_empty() { 0.0 } else { 1.0 }
+    } else {
+        edit_distance(&expected, &transcribed) as f64 / expected.len() as f64
+    };
+
+    println!(
+        "[Verify] WER {:.3} ({} expected words, {} transcribed)",
+        word_error_rate,
+        expected.len(),
+        transcribed.len()
+    );
+
+    Ok(TranscriptVerification {
+        text,
+        word_error_rate,
+        similarity: (1.0 - word_error_rate).clamp(0.0, 1.0),
+        expected_words: expected.len(),
+        transcribed_words: transcribed.len(),
+    })
+}
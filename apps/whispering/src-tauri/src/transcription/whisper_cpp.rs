@@ -0,0 +1,452 @@
+use super::export::{Segment, WordTimestamp};
+use super::ResampleQuality;
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+use serde::Deserialize;
+use std::io::Write;
+use std::path::Path;
+use std::sync::mpsc::Sender;
+use whisper_rs::{
+    FullParams, SamplingStrategy, SegmentCallbackData, WhisperContext, WhisperContextParameters,
+    WhisperState,
+};
+
+/// Decoder options for whisper.cpp that callers can tune per transcription
+///
+/// Every field is optional; unset fields keep whisper.cpp's defaults.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WhisperOptions {
+    /// Maximum segment length in characters (`set_max_len`). Useful for sizing
+    /// subtitle cues directly in the decoder, which keeps per-segment timestamps
+    /// accurate instead of re-splitting text afterwards. 0 means no limit.
+    pub max_segment_len: Option<i32>,
+    /// Split on word boundaries instead of tokens when `max_segment_len` is set
+    pub split_on_word: Option<bool>,
+    /// Number of CPU threads; falls back to the app-wide setting when unset
+    pub threads: Option<usize>,
+    /// Text fed to the decoder as preceding context, biasing it towards the
+    /// same vocabulary and style. whisper.cpp keeps only the last ~224 tokens.
+    pub initial_prompt: Option<String>,
+    /// Text file holding the initial prompt, e.g. a vocabulary list kept
+    /// alongside the model; overrides `initial_prompt` when set
+    pub prompt_file_path: Option<String>,
+    /// Output formatting: insert paragraph breaks at pauses of at least this
+    /// many milliseconds that follow the end of a sentence
+    pub paragraph_gap_ms: Option<i64>,
+    /// Resampling quality for inputs that aren't already 16kHz (default `auto`)
+    pub resample_quality: Option<ResampleQuality>,
+    /// Output filtering: remove non-speech annotations such as `[MUSIC]` or
+    /// `(applause)` from the text and segments
+    pub strip_annotations: Option<bool>,
+    /// Output filtering: pipe the final transcript through this external
+    /// command (stdin to stdout), keeping the raw transcript if it fails
+    pub post_process_command: Option<String>,
+    /// Translate the speech into English instead of transcribing it
+    pub translate: Option<bool>,
+    /// Decode with beam search of this width. Unset or below 2 (e.g. -1)
+    /// keeps greedy decoding, the default. Wider beams are slower but can be
+    /// more accurate; whisper.cpp's own CLI uses 5.
+    pub beam_size: Option<i32>,
+    /// Greedy decoding: number of candidates sampled per segment when
+    /// decoding at a temperature above zero, keeping the best (default 1).
+    /// Ignored with beam search.
+    pub best_of: Option<i32>,
+    /// Temperature fallback: a segment whose token entropy exceeds this is
+    /// decoded again at a higher temperature (whisper.cpp default 2.4). Lower
+    /// values retry repetitive output sooner.
+    pub entropy_threshold: Option<f32>,
+    /// Temperature fallback: a segment whose average token log probability is
+    /// below this is decoded again at a higher temperature (default -1.0).
+    /// Lower values accept less confident output, e.g. for noisy audio.
+    ///
+    /// whisper.cpp raises the temperature in 0.2 steps and stops at 1.0, so
+    /// either threshold triggers at most five retries per segment; past that
+    /// cap the last attempt is kept whatever its scores.
+    pub logprob_threshold: Option<f32>,
+    /// Also collect word-level timestamps from whisper.cpp's token timestamps
+    pub word_timestamps: Option<bool>,
+    /// High-pass the audio at this frequency (Hz) before transcribing, to cut
+    /// low rumble such as HVAC or traffic; 80 is typical for speech
+    pub highpass_cutoff_hz: Option<f32>,
+    /// Drop silent 30 ms frames before transcribing; segment timestamps still
+    /// refer to the original audio
+    pub vad_filter: Option<bool>,
+    /// RMS level (dBFS) below which `vad_filter` treats a frame as silent
+    /// (default -40)
+    pub vad_energy_threshold: Option<f32>,
+    /// Retry the whole transcription at rising temperatures when the output
+    /// looks unreliable, as openai-whisper does; see
+    /// `transcribe_with_temperature_fallback`
+    pub temperature_fallback: Option<bool>,
+    /// Temperature fallback: output whose zlib compression ratio exceeds this
+    /// is treated as repetitive and retried (default 2.4)
+    pub compression_ratio_threshold: Option<f32>,
+}
+
+/// Temperatures tried in turn by `transcribe_with_temperature_fallback`
+const FALLBACK_TEMPERATURES: [f32; 6] = [0.0, 0.2, 0.4, 0.6, 0.8, 1.0];
+
+/// Default `compression_ratio_threshold`, openai-whisper's value
+const DEFAULT_COMPRESSION_RATIO_THRESHOLD: f32 = 2.4;
+
+/// Default `logprob_threshold` for the temperature fallback, openai-whisper's value
+const DEFAULT_LOGPROB_THRESHOLD: f32 = -1.0;
+
+/// Text and timed segments produced by a whisper.cpp run
+pub struct WhisperOutput {
+    pub text: String,
+    pub segments: Vec<Segment>,
+    /// Empty unless `word_timestamps` was requested
+    pub words: Vec<WordTimestamp>,
+    /// Language code whisper decoded in; auto-detected when no language was given
+    pub language: Option<String>,
+}
+
+/// Whisper engine driven directly through whisper-rs
+///
+/// transcribe-rs only exposes a handful of whisper.cpp's decoder settings, so
+/// whisper models are run through whisper-rs to reach the rest. Parakeet
+/// models still go through transcribe-rs.
+pub struct WhisperCppEngine {
+    context: Option<WhisperContext>,
+    state: Option<WhisperState>,
+}
+
+impl WhisperCppEngine {
+    pub fn new() -> Self {
+        Self {
+            context: None,
+            state: None,
+        }
+    }
+
+    /// Load a model, running inference on the GPU if `use_gpu` and whisper.cpp
+    /// was built with GPU support
+    pub fn load_model(&mut self, model_path: &Path, use_gpu: bool) -> Result<(), String> {
+        let path = model_path
+            .to_str()
+            .ok_or_else(|| format!("Model path is not valid UTF-8: {:?}", model_path))?;
+
+        let mut params = WhisperContextParameters::default();
+        params.use_gpu(use_gpu);
+        let context = WhisperContext::new_with_params(path, params).map_err(|e| e.to_string())?;
+        let state = context.create_state().map_err(|e| e.to_string())?;
+
+        self.context = Some(context);
+        self.state = Some(state);
+        Ok(())
+    }
+
+    pub fn unload_model(&mut self) {
+        // The state holds a reference to the context, so drop it first
+        self.state = None;
+        self.context = None;
+    }
+
+    /// Run whisper over 16kHz mono samples
+    pub fn transcribe(
+        &mut self,
+        samples: &[f32],
+        language: Option<&str>,
+        options: &WhisperOptions,
+    ) -> Result<WhisperOutput, String> {
+        self.transcribe_streaming(samples, language, options, None)
+    }
+
+    /// Run whisper over 16kHz mono samples, sending each segment to `segments`
+    /// as soon as it is decoded
+    ///
+    /// Streamed segments are the decoder's raw output; the returned
+    /// `WhisperOutput` holds the same segments once decoding has finished.
+    ///
+    /// With `temperature_fallback`, segments are only sent once the final
+    /// attempt has been chosen.
+    pub fn transcribe_streaming(
+        &mut self,
+        samples: &[f32],
+        language: Option<&str>,
+        options: &WhisperOptions,
+        segments: Option<Sender<Segment>>,
+    ) -> Result<WhisperOutput, String> {
+        if !options.temperature_fallback.unwrap_or(false) {
+            return self.decode(samples, language, options, None, segments);
+        }
+
+        let output = self.transcribe_with_temperature_fallback(samples, language, options)?;
+        if let Some(segments) = segments {
+            for segment in &output.segments {
+                let _ = segments.send(segment.clone());
+            }
+        }
+        Ok(output)
+    }
+
+    /// Transcribe at temperature 0.0, retrying at 0.2, 0.4 ... 1.0 while the
+    /// output looks unreliable
+    ///
+    /// An attempt is accepted when its zlib compression ratio is at most
+    /// `compression_ratio_threshold` (a high ratio means repeated text) and its
+    /// mean token log probability is at least `logprob_threshold`. If no
+    /// attempt passes, the one with the highest log probability is returned.
+    ///
+    /// This mirrors openai-whisper's fallback, but over the whole recording
+    /// rather than per 30-second window. whisper-rs doesn't expose the
+    /// no-speech probability, so silence is still left to whisper.cpp's own
+    /// `no_speech_thold`.
+    pub fn transcribe_with_temperature_fallback(
+        &mut self,
+        samples: &[f32],
+        language: Option<&str>,
+        options: &WhisperOptions,
+    ) -> Result<WhisperOutput, String> {
+        let max_compression_ratio = options
+            .compression_ratio_threshold
+            .unwrap_or(DEFAULT_COMPRESSION_RATIO_THRESHOLD);
+        let min_logprob = options.logprob_threshold.unwrap_or(DEFAULT_LOGPROB_THRESHOLD);
+        let special_token_start = self.context.as_ref().map(|context| context.token_eot());
+
+        let mut best: Option<(f32, WhisperOutput)> = None;
+        for temperature in FALLBACK_TEMPERATURES {
+            let output = self.decode(samples, language, options, Some(temperature), None)?;
+            let state = self
+                .state
+                .as_ref()
+                .ok_or_else(|| "Whisper model is not loaded".to_string())?;
+            let logprob = average_logprob(state, special_token_start)?;
+            let ratio = compression_ratio(&output.text);
+            println!(
+                "[Whisper] Temperature {:.1}: compression ratio {:.2}, avg logprob {:.2}",
+                temperature, ratio, logprob
+            );
+
+            if ratio <= max_compression_ratio && logprob >= min_logprob {
+                return Ok(output);
+            }
+            let better = match &best {
+                Some((best_logprob, _)) => logprob > *best_logprob,
+                None => true,
+            };
+            if better {
+                best = Some((logprob, output));
+            }
+        }
+
+        // FALLBACK_TEMPERATURES is not empty, so at least one attempt was kept
+        best.map(|(_, output)| output)
+            .ok_or_else(|| "No transcription attempt was made".to_string())
+    }
+
+    /// One whisper.cpp run; `temperature` pins the decoder to that temperature
+    /// with its own fallback disabled
+    fn decode(
+        &mut self,
+        samples: &[f32],
+        language: Option<&str>,
+        options: &WhisperOptions,
+        temperature: Option<f32>,
+        segments: Option<Sender<Segment>>,
+    ) -> Result<WhisperOutput, String> {
+        // Token ids at or above end-of-text are special tokens (timestamps, language)
+        let special_token_start = self.context.as_ref().map(|context| context.token_eot());
+        let state = self
+            .state
+            .as_mut()
+            .ok_or_else(|| "Whisper model is not loaded".to_string())?;
+
+        // Sampling at a raised temperature is greedy, as in openai-whisper
+        let sampling = temperature.is_some_and(|temperature| temperature > 0.0);
+        let strategy = match options.beam_size {
+            // whisper.cpp doesn't implement beam search patience; -1 leaves it unset
+            Some(beam_size) if beam_size > 1 && !sampling => SamplingStrategy::BeamSearch {
+                beam_size,
+                patience: -1.0,
+            },
+            _ => SamplingStrategy::Greedy {
+                best_of: options.best_of.unwrap_or(1).max(1),
+            },
+        };
+        let mut params = FullParams::new(strategy);
+        params.set_language(language);
+        params.set_print_special(false);
+        params.set_print_progress(false);
+        params.set_print_realtime(false);
+        params.set_print_timestamps(false);
+        params.set_suppress_blank(true);
+        params.set_suppress_non_speech_tokens(true);
+        params.set_no_speech_thold(0.2);
+
+        if let Some(max_len) = options.max_segment_len {
+            params.set_max_len(max_len);
+            // whisper.cpp only honours max_len with token-level timestamps enabled
+            params.set_token_timestamps(max_len > 0);
+        }
+        let word_timestamps = options.word_timestamps.unwrap_or(false);
+        if word_timestamps {
+            params.set_token_timestamps(true);
+        }
+        if let Some(split_on_word) = options.split_on_word {
+            params.set_split_on_word(split_on_word);
+        }
+        if let Some(translate) = options.translate {
+            params.set_translate(translate);
+        }
+        if let Some(entropy_threshold) = options.entropy_threshold {
+            params.set_entropy_thold(entropy_threshold);
+        }
+        if let Some(logprob_threshold) = options.logprob_threshold {
+            params.set_logprob_thold(logprob_threshold);
+        }
+        if let Some(temperature) = temperature {
+            params.set_temperature(temperature);
+            params.set_temperature_inc(0.0);
+        }
+        if let Some(threads) = options.threads {
+            params.set_n_threads(threads as i32);
+        }
+        if let Some(prompt) = options.initial_prompt.as_deref() {
+            // whisper-rs panics on interior NUL bytes
+            params.set_initial_prompt(&prompt.replace('\0', ""));
+        }
+
+        if let Some(segments) = segments {
+            params.set_segment_callback_safe_lossy(move |data: SegmentCallbackData| {
+                // The receiver may have gone away; decoding carries on regardless
+                let _ = segments.send(Segment {
+                    start_ms: data.start_timestamp * 10,
+                    end_ms: data.end_timestamp * 10,
+                    text: data.text.trim().to_string(),
+                });
+            });
+        }
+
+        state.full(params, samples).map_err(|e| e.to_string())?;
+
+        let num_segments = state.full_n_segments().map_err(|e| e.to_string())?;
+        let mut segments = Vec::with_capacity(num_segments.max(0) as usize);
+        let mut text = String::new();
+        let mut words = Vec::new();
+
+        for i in 0..num_segments {
+            let segment_text = state.full_get_segment_text_lossy(i).map_err(|e| e.to_string())?;
+            // whisper.cpp timestamps are in centiseconds
+            let start_ms = state.full_get_segment_t0(i).map_err(|e| e.to_string())? * 10;
+            let end_ms = state.full_get_segment_t1(i).map_err(|e| e.to_string())? * 10;
+
+            text.push_str(&segment_text);
+            segments.push(Segment {
+                start_ms,
+                end_ms,
+                text: segment_text.trim().to_string(),
+            });
+
+            if word_timestamps {
+                collect_words(state, i, special_token_start, &mut words)?;
+            }
+        }
+
+        let language = state
+            .full_lang_id_from_state()
+            .ok()
+            .and_then(whisper_rs::get_lang_str)
+            .map(str::to_string);
+
+        Ok(WhisperOutput {
+            text,
+            segments,
+            words,
+            language,
+        })
+    }
+}
+
+/// Mean log probability of the text tokens of the last run
+fn average_logprob(state: &WhisperState, special_token_start: Option<i32>) -> Result<f32, String> {
+    let mut sum = 0.0f32;
+    let mut count = 0usize;
+    for segment in 0..state.full_n_segments().map_err(|e| e.to_string())? {
+        for token in 0..state.full_n_tokens(segment).map_err(|e| e.to_string())? {
+            let data = state.full_get_token_data(segment, token).map_err(|e| e.to_string())?;
+            if special_token_start.is_some_and(|start| data.id >= start) {
+                continue;
+            }
+            sum += data.plog;
+            count += 1;
+        }
+    }
+    Ok(if count == 0 { 0.0 } else { sum / count as f32 })
+}
+
+/// Text length over its zlib-compressed length; repetitive text compresses well
+fn compression_ratio(text: &str) -> f32 {
+    if text.is_empty() {
+        return 0.0;
+    }
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    let compressed = encoder
+        .write_all(text.as_bytes())
+        .and_then(|_| encoder.finish())
+        .unwrap_or_default();
+    text.len() as f32 / compressed.len().max(1) as f32
+}
+
+/// Append the words of segment `segment` to `words`, built from its tokens
+///
+/// whisper's BPE tokens start a new word when they begin with a space, so
+/// consecutive tokens are merged until the next one that does. Timestamps
+/// come from the first and last token of each word.
+fn collect_words(
+    state: &WhisperState,
+    segment: i32,
+    special_token_start: Option<i32>,
+    words: &mut Vec<WordTimestamp>,
+) -> Result<(), String> {
+    let num_tokens = state.full_n_tokens(segment).map_err(|e| e.to_string())?;
+    // Probabilities of the tokens in the word being built
+    let mut probabilities: Vec<f32> = Vec::new();
+    let mut current: Option<WordTimestamp> = None;
+
+    let mut finish = |word: Option<WordTimestamp>, probabilities: &mut Vec<f32>| {
+        if let Some(mut word) = word {
+            word.word = word.word.trim().to_string();
+            if !word.word.is_empty() {
+                word.probability =
+                    probabilities.iter().sum::<f32>() / probabilities.len().max(1) as f32;
+                words.push(word);
+            }
+        }
+        probabilities.clear();
+    };
+
+    for token in 0..num_tokens {
+        let data = state.full_get_token_data(segment, token).map_err(|e| e.to_string())?;
+        if special_token_start.is_some_and(|start| data.id >= start) {
+            continue;
+        }
+        let token_text = state
+            .full_get_token_text_lossy(segment, token)
+            .map_err(|e| e.to_string())?;
+
+        // whisper.cpp timestamps are in centiseconds
+        let (start_ms, end_ms) = (data.t0 * 10, data.t1 * 10);
+        match current.as_mut() {
+            Some(word) if !token_text.starts_with(' ') => {
+                word.word.push_str(&token_text);
+                word.end_ms = end_ms;
+            }
+            _ => {
+                finish(current.take(), &mut probabilities);
+                current = Some(WordTimestamp {
+                    word: token_text,
+                    start_ms,
+                    end_ms,
+                    probability: 0.0,
+                });
+            }
+        }
+        probabilities.push(data.p);
+    }
+    finish(current, &mut probabilities);
+
+    Ok(())
+}
@@ -178,10 +178,12 @@ export function createCpalRecorderService(): RecorderService {
 			const { error: initRecordingSessionError } = await invoke(
 				'init_recording_session',
 				{
-					deviceIdentifier,
-					recordingId,
-					outputFolder,
-					sampleRate: sampleRateNum,
+					options: {
+						deviceIdentifier,
+						recordingId,
+						outputFolder,
+						sampleRate: sampleRateNum,
+					},
 				},
 			);
 			if (initRecordingSessionError)
@@ -50,7 +50,7 @@ export const PARAKEET_MODELS: readonly ParakeetModelConfig[] = [
 ] as const;
 
 const ParakeetErrorType = type({
-	name: "'AudioReadError' | 'FfmpegNotFoundError' | 'ModelLoadError' | 'TranscriptionError'",
+	name: "'AudioFormatNotSupported' | 'AudioReadError' | 'FfmpegNotFoundError' | 'ModelLoadError' | 'OutOfMemory' | 'TranscriptionError'",
 	message: 'string',
 });
 
@@ -156,6 +156,28 @@ export function createParakeetTranscriptionService() {
 								},
 							});
 
+						case 'AudioFormatNotSupported':
+							return WhisperingErr({
+								title: '🎵 Audio Format Not Supported',
+								description: `${error.message} Record with CPAL or in voice-activated mode (both produce WAV files), or convert the file to WAV.`,
+								action: {
+									type: 'link',
+									label: 'Recording settings',
+									href: '/settings/recording',
+								},
+							});
+
+						case 'OutOfMemory':
+							return WhisperingErr({
+								title: '🧠 Out of Memory',
+								description: error.message,
+								action: {
+									type: 'link',
+									label: 'Choose a smaller model',
+									href: '/settings/transcription',
+								},
+							});
+
 						case 'AudioReadError':
 							return WhisperingErr({
 								title: '🔊 Audio Read Error',
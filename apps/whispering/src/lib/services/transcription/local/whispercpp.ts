@@ -63,7 +63,7 @@ export const WHISPER_MODELS: readonly WhisperModelConfig[] = [
 ] as const;
 
 const WhisperCppErrorType = type({
-	name: "'AudioReadError' | 'FfmpegNotFoundError' | 'GpuError' | 'ModelLoadError' | 'TranscriptionError'",
+	name: "'AudioFormatNotSupported' | 'AudioReadError' | 'FfmpegNotFoundError' | 'GpuError' | 'ModelLoadError' | 'OutOfMemory' | 'TranscriptionError'",
 	message: 'string',
 });
 
@@ -166,6 +166,28 @@ export function createWhisperCppTranscriptionService() {
 								},
 							});
 
+						case 'AudioFormatNotSupported':
+							return WhisperingErr({
+								title: '🎵 Audio Format Not Supported',
+								description: `${error.message} Record with CPAL or in voice-activated mode (both produce WAV files), or convert the file to WAV.`,
+								action: {
+									type: 'link',
+									label: 'Recording settings',
+									href: '/settings/recording',
+								},
+							});
+
+						case 'OutOfMemory':
+							return WhisperingErr({
+								title: '🧠 Out of Memory',
+								description: error.message,
+								action: {
+									type: 'link',
+									label: 'Choose a smaller model',
+									href: '/settings/transcription',
+								},
+							});
+
 						case 'AudioReadError':
 							return WhisperingErr({
 								title: '🔊 Audio Read Error',